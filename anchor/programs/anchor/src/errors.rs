@@ -32,4 +32,110 @@ pub enum FflError {
     PickResolved,
     #[msg("Pick not found")]
     PickNotFound,
+    #[msg("Signer is not the resolver authority")]
+    UnauthorizedResolver,
+    #[msg("Market ID is too long")]
+    MarketIdTooLong,
+    #[msg("Market already resolved")]
+    MarketAlreadyResolved,
+    #[msg("Market not resolved yet")]
+    MarketNotResolved,
+    #[msg("Pick does not belong to this market")]
+    MarketMismatch,
+    #[msg("Probability must be between 0 and 10000")]
+    InvalidProbability,
+    #[msg("Batch accounts must be writable (League, DraftPick, PlayerState) triples")]
+    InvalidBatchAccounts,
+    #[msg("Scoring rules are out of range")]
+    InvalidScoringRules,
+    #[msg("Invalid session settings")]
+    InvalidSessionConfig,
+    #[msg("Current session is not complete")]
+    SessionNotComplete,
+    #[msg("Season already ended")]
+    SeasonAlreadyEnded,
+    #[msg("Not all sessions have been played")]
+    SeasonNotFinished,
+    #[msg("Some picks are still unresolved")]
+    PicksUnresolved,
+    #[msg("Signer is not a league commissioner")]
+    NotCommissioner,
+    #[msg("Too many co-commissioners")]
+    TooManyCoCommissioners,
+    #[msg("Already a co-commissioner")]
+    AlreadyCoCommissioner,
+    #[msg("Co-commissioner not found")]
+    CoCommissionerNotFound,
+    #[msg("Entropy already revealed")]
+    EntropyAlreadyRevealed,
+    #[msg("Revealed secret does not match commitment")]
+    InvalidEntropyReveal,
+    #[msg("Not every player has revealed their entropy")]
+    EntropyNotRevealed,
+    #[msg("Token accounts are required for this league")]
+    MissingTokenAccounts,
+    #[msg("Mint does not match the league currency")]
+    InvalidMint,
+    #[msg("League currency can only change before anyone joins")]
+    CurrencyLocked,
+    #[msg("Token account is not owned by the expected wallet")]
+    InvalidTokenAccount,
+    #[msg("Invalid payout structure")]
+    InvalidPayoutStructure,
+    #[msg("Every player state in the league must be provided exactly once")]
+    InvalidStandingsAccounts,
+    #[msg("League is not completed")]
+    LeagueNotCompleted,
+    #[msg("Payout already claimed")]
+    AlreadyClaimed,
+    #[msg("Player is not in the standings")]
+    NotInStandings,
+    #[msg("Fee exceeds the allowed maximum")]
+    FeeTooHigh,
+    #[msg("Deadline must be in the future")]
+    InvalidDeadline,
+    #[msg("Only a commissioner can cancel before the setup deadline")]
+    CancelNotAllowed,
+    #[msg("League is not cancelled")]
+    LeagueNotCancelled,
+    #[msg("Player is not in this league")]
+    NotInLeague,
+    #[msg("Not enough players to start the draft")]
+    NotEnoughPlayers,
+    #[msg("Invalid min/max player limits")]
+    InvalidPlayerLimits,
+    #[msg("Ratings were already updated for this league")]
+    RatingsAlreadyUpdated,
+    #[msg("Profile accounts must match the standings, in order")]
+    InvalidProfileAccounts,
+    #[msg("Pick time limit must be between 0 and 7 days")]
+    InvalidPickTimeLimit,
+    #[msg("This league has no pick clock")]
+    NoPickClock,
+    #[msg("The current pick is still on the clock")]
+    PickClockRunning,
+    #[msg("Draft queue is too long")]
+    DraftQueueTooLong,
+    #[msg("Draft pick accounts must match the draft queue, in order")]
+    InvalidQueueAccounts,
+    #[msg("Drafter has queued picks; use auto_pick")]
+    DraftQueueNotEmpty,
+    #[msg("League is locked for the draft")]
+    LeagueLocked,
+    #[msg("League must be locked before entropy can be revealed")]
+    LeagueNotLocked,
+    #[msg("The entropy reveal window has closed")]
+    RevealWindowClosed,
+    #[msg("The entropy reveal window is still open")]
+    RevealWindowOpen,
+    #[msg("No recent slot hash is available")]
+    SlotHashUnavailable,
+    #[msg("This mint uses a Token-2022 extension that the league vault does not support")]
+    UnsupportedMintExtension,
+    #[msg("The draft slot's hash has aged out of the SlotHashes sysvar")]
+    DraftSlotExpired,
+    #[msg("The draft slot's hash is still available")]
+    DraftSlotNotExpired,
+    #[msg("Draft queues can't be closed while a draft is running")]
+    DraftInProgress,
 }
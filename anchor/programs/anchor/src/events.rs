@@ -0,0 +1,243 @@
+use anchor_lang::prelude::*;
+use crate::state::{LeagueMode, MarketOutcome, PickOutcome, PointsBreakdown, Prediction};
+
+// Indexers decode these by field order: only ever append new fields at the end.
+
+#[event]
+pub struct LeagueCreated {
+    pub league_id: u64,
+    pub league: Pubkey,
+    pub creator: Pubkey,
+    pub mode: LeagueMode,
+    pub buy_in_amount: u64,
+    pub sessions_total: u8,
+    pub rounds_per_session: u8,
+    pub min_players: u8,
+    pub max_players: u8,
+}
+
+/// The league will take buy-ins in this SPL mint instead of SOL.
+#[event]
+pub struct LeagueCurrencySet {
+    pub league_id: u64,
+    pub mint: Pubkey,
+}
+
+/// The config admin overrode (or, with None, cleared) the league's resolver.
+#[event]
+pub struct LeagueResolverSet {
+    pub league_id: u64,
+    pub resolver: Option<Pubkey>,
+}
+
+#[event]
+pub struct CoCommissionerAdded {
+    pub league_id: u64,
+    pub co_commissioner: Pubkey,
+}
+
+#[event]
+pub struct CoCommissionerRemoved {
+    pub league_id: u64,
+    pub co_commissioner: Pubkey,
+}
+
+#[event]
+pub struct PlayerJoined {
+    pub league_id: u64,
+    pub player: Pubkey,
+    pub player_count: u8,
+}
+
+/// A player left (or was removed for not revealing) before the draft and got their buy-in back.
+#[event]
+pub struct PlayerLeft {
+    pub league_id: u64,
+    pub player: Pubkey,
+    pub player_count: u8,
+    pub refund: u64,
+}
+
+#[event]
+pub struct LeagueCancelled {
+    pub league_id: u64,
+    pub cancelled_by: Pubkey,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub league_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+/// Joins are closed and players have until `reveal_deadline` to reveal their entropy.
+#[event]
+pub struct LeagueLocked {
+    pub league_id: u64,
+    pub reveal_deadline: i64,
+}
+
+#[event]
+pub struct EntropyRevealed {
+    pub league_id: u64,
+    pub player: Pubkey,
+    pub reveals: u8,
+}
+
+/// Every remaining player has revealed; `start_draft` can run once `draft_slot` is reached.
+/// Emitted again by `reschedule_draft` if the slot's hash aged out before the draft started.
+#[event]
+pub struct DraftSlotScheduled {
+    pub league_id: u64,
+    pub draft_slot: u64,
+}
+
+/// Emitted when a session's draft opens, at `start_draft` and at each `advance_session`.
+#[event]
+pub struct DraftStarted {
+    pub league_id: u64,
+    pub session: u8,
+    pub draft_order: Vec<Pubkey>,
+}
+
+/// Every slot of the session's draft is used and the session is live.
+#[event]
+pub struct DraftCompleted {
+    pub league_id: u64,
+    pub session: u8,
+}
+
+#[event]
+pub struct PickMade {
+    pub league_id: u64,
+    pub draft_pick: Pubkey,
+    pub player: Pubkey,
+    pub session: u8,
+    pub pick_index: u8,
+    pub market_id: String,
+    pub prediction: Prediction,
+    pub snapshot_odds: u32,
+}
+
+/// A drafter ran out of time and their slot was forfeited.
+#[event]
+pub struct TurnSkipped {
+    pub league_id: u64,
+    pub player: Pubkey,
+    pub session: u8,
+    pub pick_index: u8,
+}
+
+#[event]
+pub struct MarketRegistered {
+    pub market_id: String,
+    pub close_time: i64,
+}
+
+#[event]
+pub struct MarketSettled {
+    pub market_id: String,
+    pub outcome: MarketOutcome,
+    pub final_prob: u32,
+}
+
+#[event]
+pub struct PickResolved {
+    pub league_id: u64,
+    pub draft_pick: Pubkey,
+    pub player: Pubkey,
+    pub market_id: String,
+    pub outcome: PickOutcome,
+    pub points: i32, // breakdown.total()
+    pub breakdown: PointsBreakdown,
+}
+
+/// Summary of one batch_resolve_market call; `skipped` picks were already resolved.
+#[event]
+pub struct BatchResolved {
+    pub market_id: String,
+    pub resolved: u16,
+    pub skipped: u16,
+}
+
+#[event]
+pub struct TradeProposed {
+    pub league_id: u64,
+    pub trade_id: u64,
+    pub proposer: Pubkey,
+    pub receiver: Pubkey,
+    pub proposer_pick: Pubkey,
+    pub receiver_pick: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct TradeAccepted {
+    pub league_id: u64,
+    pub trade_id: u64,
+    pub proposer: Pubkey,
+    pub receiver: Pubkey,
+}
+
+#[event]
+pub struct TradeRejected {
+    pub league_id: u64,
+    pub trade_id: u64,
+    pub proposer: Pubkey,
+    pub receiver: Pubkey,
+}
+
+#[event]
+pub struct SeasonEnded {
+    pub league_id: u64,
+    pub sessions_played: u8,
+    pub total_points: i64,
+}
+
+/// Fees set aside from the pool at finalize_standings; each is paid out by claim_fees.
+#[event]
+pub struct FeesCollected {
+    pub league_id: u64,
+    pub gross_pool: u64,
+    pub protocol_fee: u64,
+    pub fee_recipient: Pubkey,
+    pub commissioner_fee: u64,
+    pub commissioner: Pubkey,
+}
+
+/// XP earned in a league. `xp` and `level` are the player's new totals for that league.
+#[event]
+pub struct XpAwarded {
+    pub league_id: u64,
+    pub player: Pubkey,
+    pub amount: u32,
+    pub xp: u32,
+    pub level: u32,
+}
+
+/// A league's result applied to a wallet's Profile by update_ratings. `xp` and `level` are lifetime totals.
+#[event]
+pub struct ProfileUpdated {
+    pub wallet: Pubkey,
+    pub league_id: u64,
+    pub rating: u16,
+    pub rating_change: i32,
+    pub xp: u64,
+    pub level: u32,
+}
+
+#[event]
+pub struct FeesClaimed {
+    pub league_id: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PayoutClaimed {
+    pub league_id: u64,
+    pub player: Pubkey,
+    pub rank: u8,
+    pub amount: u64,
+}
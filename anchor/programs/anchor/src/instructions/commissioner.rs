@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::FflError;
+use crate::events::{CoCommissionerAdded, CoCommissionerRemoved};
+
+pub fn add_co_commissioner(ctx: Context<ManageCoCommissioners>, co_commissioner: Pubkey) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+
+    require!(!league.is_commissioner(&co_commissioner), FflError::AlreadyCoCommissioner);
+    require!(league.co_commissioners.len() < MAX_CO_COMMISSIONERS, FflError::TooManyCoCommissioners);
+
+    league.co_commissioners.push(co_commissioner);
+    league.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(CoCommissionerAdded {
+        league_id: league.league_id,
+        co_commissioner,
+    });
+
+    Ok(())
+}
+
+pub fn remove_co_commissioner(ctx: Context<ManageCoCommissioners>, co_commissioner: Pubkey) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+
+    let index = league.co_commissioners
+        .iter()
+        .position(|key| *key == co_commissioner)
+        .ok_or(FflError::CoCommissionerNotFound)?;
+
+    league.co_commissioners.remove(index);
+    league.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(CoCommissionerRemoved {
+        league_id: league.league_id,
+        co_commissioner,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ManageCoCommissioners<'info> {
+    // Only the creator can delegate commissioner rights
+    #[account(mut, has_one = creator @ FflError::NotCommissioner)]
+    pub league: Account<'info, League>,
+    pub creator: Signer<'info>,
+}
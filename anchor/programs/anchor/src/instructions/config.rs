@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::FflError;
+use crate::events::LeagueResolverSet;
+use crate::program::Anchor;
+
+pub fn initialize_config(ctx: Context<InitializeConfig>, resolver: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.resolver = resolver;
+    config.protocol_fee_bps = 0;
+    config.fee_recipient = ctx.accounts.admin.key();
+    config.bump = ctx.bumps.config;
+    Ok(())
+}
+
+pub fn set_resolver(ctx: Context<UpdateConfig>, new_resolver: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.resolver = new_resolver;
+    Ok(())
+}
+
+pub fn set_fee_config(ctx: Context<UpdateConfig>, protocol_fee_bps: u16, fee_recipient: Pubkey) -> Result<()> {
+    require!(protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS, FflError::FeeTooHigh);
+
+    let config = &mut ctx.accounts.config;
+    config.protocol_fee_bps = protocol_fee_bps;
+    config.fee_recipient = fee_recipient;
+    Ok(())
+}
+
+pub fn set_league_resolver(ctx: Context<SetLeagueResolver>, resolver: Option<Pubkey>) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+    league.resolver = resolver;
+    league.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(LeagueResolverSet {
+        league_id: league.league_id,
+        resolver,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        seeds = [b"config"],
+        bump,
+        payer = admin,
+        space = Config::LEN
+    )]
+    pub config: Account<'info, Config>,
+
+    // Only the program's upgrade authority may create the config
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Anchor>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ FflError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ FflError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetLeagueResolver<'info> {
+    // Only the protocol admin may hand a league's resolution to someone else
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ FflError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub league: Account<'info, League>,
+}
@@ -0,0 +1,282 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use crate::state::*;
+use crate::errors::FflError;
+use crate::events::{DraftCompleted, PickMade, TurnSkipped};
+use crate::xp::{award_xp, XP_PICK_MADE};
+
+/// Forfeits the current drafter's slot once their pick clock has run out.
+///
+/// Permissionless, so one absent player can't stall the draft. Only for drafters with an
+/// empty or missing draft queue: anyone with queued picks must be resolved by `auto_pick`,
+/// which forfeits the slot itself if every queued market is taken.
+pub fn skip_turn(ctx: Context<SkipTurn>) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(league.state == LeagueState::Drafting, FflError::NotDrafting);
+    require!(league.pick_time_limit > 0, FflError::NoPickClock);
+    require!(now > league.pick_deadline, FflError::PickClockRunning);
+
+    let draft_queue = &ctx.accounts.draft_queue;
+    if draft_queue.owner == &crate::ID {
+        let draft_queue = DraftQueue::try_deserialize(&mut &draft_queue.try_borrow_data()?[..])?;
+        require!(draft_queue.entries.is_empty(), FflError::DraftQueueNotEmpty);
+    }
+
+    forfeit_turn(league, now)
+}
+
+/// Replaces the caller's draft queue. Allowed until the season's drafting is over.
+pub fn set_draft_queue(ctx: Context<SetDraftQueue>, entries: Vec<QueuedPick>) -> Result<()> {
+    let league = &ctx.accounts.league;
+
+    require!(
+        matches!(league.state, LeagueState::Setup | LeagueState::Drafting | LeagueState::Active),
+        FflError::SeasonAlreadyEnded
+    );
+    require!(entries.len() <= MAX_QUEUE_LEN, FflError::DraftQueueTooLong);
+    for entry in &entries {
+        require!(entry.market_id.len() <= MAX_MARKET_ID_LEN, FflError::MarketIdTooLong);
+        require!(entry.snapshot_odds <= 10000, FflError::InvalidProbability);
+    }
+
+    let draft_queue = &mut ctx.accounts.draft_queue;
+    draft_queue.league = league.key();
+    draft_queue.player = ctx.accounts.player.key();
+    draft_queue.entries = entries;
+    draft_queue.bump = ctx.bumps.draft_queue;
+
+    Ok(())
+}
+
+/// Closes a draft queue and returns its rent to the player. Not while a draft is running,
+/// where the queue decides whether the player's turn can be skipped.
+pub fn close_draft_queue(ctx: Context<CloseDraftQueue>) -> Result<()> {
+    require!(ctx.accounts.league.state != LeagueState::Drafting, FflError::DraftInProgress);
+    Ok(())
+}
+
+/// Drafts for a player whose pick clock has run out, from their draft queue.
+///
+/// Permissionless; the caller pays rent for the new DraftPick. `remaining_accounts` must
+/// hold the DraftPick PDA for each queue entry (writable), in queue order. The first one
+/// that doesn't exist yet is created; if every queued market is taken, the slot is forfeited
+/// as with `skip_turn`.
+pub fn auto_pick<'info>(ctx: Context<'_, '_, 'info, 'info, AutoPick<'info>>) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+    let draft_queue = &ctx.accounts.draft_queue;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(league.state == LeagueState::Drafting, FflError::NotDrafting);
+    require!(league.pick_time_limit > 0, FflError::NoPickClock);
+    require!(now > league.pick_deadline, FflError::PickClockRunning);
+    require!(
+        ctx.remaining_accounts.len() == draft_queue.entries.len(),
+        FflError::InvalidQueueAccounts
+    );
+
+    let league_key = league.key();
+    for (entry, pick_info) in draft_queue.entries.iter().zip(ctx.remaining_accounts) {
+        let prediction_seed = [match entry.prediction { Prediction::Yes => 1, Prediction::No => 0 }];
+        let session_seed = [league.current_session];
+        let seeds: [&[u8]; 5] = [
+            b"draft_pick",
+            league_key.as_ref(),
+            &session_seed,
+            entry.market_id.as_bytes(),
+            &prediction_seed,
+        ];
+        let (expected, bump) = Pubkey::find_program_address(&seeds, &crate::ID);
+        require!(pick_info.key() == expected && pick_info.is_writable, FflError::InvalidQueueAccounts);
+
+        // Someone already drafted this market with this prediction
+        if pick_info.owner == &crate::ID {
+            continue;
+        }
+
+        let bump_seed = [bump];
+        let signer_seeds: [&[u8]; 6] = [seeds[0], seeds[1], seeds[2], seeds[3], seeds[4], &bump_seed];
+        create_pick_account(
+            &ctx.accounts.payer.to_account_info(),
+            pick_info,
+            &ctx.accounts.system_program.to_account_info(),
+            &signer_seeds,
+        )?;
+
+        let draft_pick = DraftPick::new(
+            league,
+            ctx.accounts.drafter.key(),
+            entry.market_id.clone(),
+            entry.prediction,
+            entry.snapshot_odds,
+            bump,
+        );
+        draft_pick.try_serialize(&mut &mut pick_info.try_borrow_mut_data()?[..])?;
+
+        return record_pick(league, &expected, &draft_pick, &mut ctx.accounts.player_state);
+    }
+
+    forfeit_turn(league, now)
+}
+
+/// Awards pick XP, emits PickMade and hands the turn to the next drafter.
+pub fn record_pick(
+    league: &mut League,
+    draft_pick_key: &Pubkey,
+    draft_pick: &DraftPick,
+    player_state: &mut PlayerState,
+) -> Result<()> {
+    award_xp(player_state, XP_PICK_MADE);
+
+    emit!(PickMade {
+        league_id: league.league_id,
+        draft_pick: *draft_pick_key,
+        player: draft_pick.player,
+        session: draft_pick.session_index,
+        pick_index: draft_pick.pick_index,
+        market_id: draft_pick.market_id.clone(),
+        prediction: draft_pick.prediction,
+        snapshot_odds: draft_pick.snapshot_odds,
+    });
+
+    advance_turn(league, Clock::get()?.unix_timestamp);
+    Ok(())
+}
+
+/// Gives up the current drafter's slot. It is counted as both picked and resolved, so it
+/// scores nothing and never holds up the session.
+fn forfeit_turn(league: &mut League, now: i64) -> Result<()> {
+    let player = league.current_drafter().ok_or(FflError::SessionNotActive)?;
+    let pick_index = league.session_picks_count as u8;
+
+    league.session_resolved_count += 1;
+
+    emit!(TurnSkipped {
+        league_id: league.league_id,
+        player,
+        session: league.current_session,
+        pick_index,
+    });
+
+    advance_turn(league, now);
+    Ok(())
+}
+
+/// Hands the turn on, and announces the session going live once the last slot is used.
+fn advance_turn(league: &mut League, now: i64) {
+    league.advance_draft_turn(now);
+
+    if league.state == LeagueState::Active {
+        emit!(DraftCompleted {
+            league_id: league.league_id,
+            session: league.current_session,
+        });
+    }
+}
+
+/// Creates a program-owned DraftPick account at a PDA. Works even if someone has already
+/// sent lamports to the address, which would make a plain create_account fail.
+fn create_pick_account<'info>(
+    payer: &AccountInfo<'info>,
+    pick_info: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let space = DraftPick::LEN;
+    let rent = Rent::get()?.minimum_balance(space);
+
+    if pick_info.lamports() == 0 {
+        let ix = system_instruction::create_account(&payer.key(), &pick_info.key(), rent, space as u64, &crate::ID);
+        invoke_signed(&ix, &[payer.clone(), pick_info.clone(), system_program.clone()], &[signer_seeds])?;
+        return Ok(());
+    }
+
+    let shortfall = rent.saturating_sub(pick_info.lamports());
+    if shortfall > 0 {
+        let ix = system_instruction::transfer(&payer.key(), &pick_info.key(), shortfall);
+        invoke(&ix, &[payer.clone(), pick_info.clone(), system_program.clone()])?;
+    }
+    let ix = system_instruction::allocate(&pick_info.key(), space as u64);
+    invoke_signed(&ix, &[pick_info.clone(), system_program.clone()], &[signer_seeds])?;
+    let ix = system_instruction::assign(&pick_info.key(), &crate::ID);
+    invoke_signed(&ix, &[pick_info.clone(), system_program.clone()], &[signer_seeds])?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SkipTurn<'info> {
+    #[account(mut)]
+    pub league: Account<'info, League>,
+
+    /// CHECK: The player on the clock; only used as a seed
+    #[account(constraint = league.current_drafter() == Some(drafter.key()) @ FflError::NotYourTurn)]
+    pub drafter: AccountInfo<'info>,
+
+    /// CHECK: The drafter's DraftQueue PDA, which may not exist; read in the handler
+    #[account(seeds = [b"draft_queue", league.key().as_ref(), drafter.key().as_ref()], bump)]
+    pub draft_queue: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetDraftQueue<'info> {
+    #[account(constraint = league.players.contains(&player.key()) @ FflError::NotInLeague)]
+    pub league: Account<'info, League>,
+
+    #[account(
+        init_if_needed,
+        seeds = [b"draft_queue", league.key().as_ref(), player.key().as_ref()],
+        bump,
+        payer = player,
+        space = DraftQueue::LEN
+    )]
+    pub draft_queue: Account<'info, DraftQueue>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseDraftQueue<'info> {
+    pub league: Account<'info, League>,
+
+    #[account(
+        mut,
+        seeds = [b"draft_queue", league.key().as_ref(), player.key().as_ref()],
+        bump = draft_queue.bump,
+        close = player
+    )]
+    pub draft_queue: Account<'info, DraftQueue>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AutoPick<'info> {
+    #[account(mut)]
+    pub league: Account<'info, League>,
+
+    /// CHECK: The player on the clock; only used as a seed and as the pick's owner
+    #[account(constraint = league.current_drafter() == Some(drafter.key()) @ FflError::NotYourTurn)]
+    pub drafter: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"draft_queue", league.key().as_ref(), drafter.key().as_ref()],
+        bump = draft_queue.bump
+    )]
+    pub draft_queue: Account<'info, DraftQueue>,
+
+    #[account(
+        mut,
+        seeds = [b"player_state", league.key().as_ref(), drafter.key().as_ref()],
+        bump = player_state.bump
+    )]
+    pub player_state: Account<'info, PlayerState>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
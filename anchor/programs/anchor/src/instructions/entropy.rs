@@ -0,0 +1,344 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::FflError;
+use crate::events::{DraftSlotScheduled, EntropyRevealed, LeagueLocked, PlayerLeft};
+use super::refund::refund_buy_in;
+
+// Draft order uses commit-reveal: each player commits to hash(secret || player) in
+// join_league and reveals the secret here before the draft. Contributions are XORed,
+// so the combined seed doesn't depend on reveal order and no single party can pick it.
+//
+// Reveals only open once the league is locked (full, or locked by a commissioner), so
+// nobody can join after seeing other reveals. Players who miss the reveal window are
+// removed and refunded. When the last remaining player has revealed, the league fixes a
+// draft slot a little in the future, and start_draft mixes in that slot's hash. Nobody
+// knows it while revealing, and nobody can pick a different slot by choosing when to call
+// start_draft, so withholding a reveal or timing the start can't steer the order.
+
+/// Closes a league to joins and opens the entropy reveal window.
+pub fn lock_league(ctx: Context<LockLeague>) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+
+    require!(league.state == LeagueState::Setup, FflError::NotSetup);
+    require!(!league.is_locked(), FflError::LeagueLocked);
+    require!(league.players.len() >= league.min_players as usize, FflError::NotEnoughPlayers);
+
+    league.lock(Clock::get()?.unix_timestamp);
+
+    emit!(LeagueLocked {
+        league_id: league.league_id,
+        reveal_deadline: league.reveal_deadline,
+    });
+
+    Ok(())
+}
+
+pub fn reveal_entropy(ctx: Context<RevealEntropy>, secret: [u8; 32]) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+    let player_state = &mut ctx.accounts.player_state;
+    let player = ctx.accounts.player.key();
+
+    require!(league.state == LeagueState::Setup, FflError::NotSetup);
+    require!(league.is_locked(), FflError::LeagueNotLocked);
+    require!(Clock::get()?.unix_timestamp <= league.reveal_deadline, FflError::RevealWindowClosed);
+    require!(!player_state.entropy_revealed, FflError::EntropyAlreadyRevealed);
+    require!(
+        hashv(&[&secret, player.as_ref()]).to_bytes() == player_state.entropy_commitment,
+        FflError::InvalidEntropyReveal
+    );
+
+    let contribution = entropy_contribution(&secret);
+    xor_into(&mut league.draft_seed, &contribution);
+    league.entropy_reveals += 1;
+    player_state.entropy_revealed = true;
+    player_state.entropy_contribution = contribution;
+
+    emit!(EntropyRevealed {
+        league_id: league.league_id,
+        player,
+        reveals: league.entropy_reveals,
+    });
+
+    schedule_draft(league)
+}
+
+/// Removes a player who didn't reveal before the deadline, refunds their buy-in and closes
+/// their PlayerState. Permissionless, so one silent player can't hold up the draft.
+pub fn remove_unrevealed(ctx: Context<RemoveUnrevealed>) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+    let player = ctx.accounts.player.key();
+
+    require!(league.state == LeagueState::Setup, FflError::NotSetup);
+    require!(league.is_locked(), FflError::LeagueNotLocked);
+    require!(Clock::get()?.unix_timestamp > league.reveal_deadline, FflError::RevealWindowOpen);
+    require!(!ctx.accounts.player_state.entropy_revealed, FflError::EntropyAlreadyRevealed);
+
+    let index = league.players
+        .iter()
+        .position(|key| *key == player)
+        .ok_or(FflError::NotInLeague)?;
+    league.players.remove(index);
+    league.updated_at = Clock::get()?.unix_timestamp;
+    schedule_draft(league)?;
+
+    emit!(PlayerLeft {
+        league_id: league.league_id,
+        player,
+        player_count: league.players.len() as u8,
+        refund: league.buy_in_amount,
+    });
+
+    refund_buy_in(
+        &ctx.accounts.league,
+        &ctx.accounts.player,
+        &ctx.accounts.prize_pool_vault,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.player_token_account,
+        &ctx.accounts.token_program,
+        &ctx.accounts.system_program,
+    )
+}
+
+/// Picks a new draft slot once the old one's hash has aged out of SlotHashes, so a draft
+/// that nobody started in time isn't stuck. Permissionless, like `start_draft`.
+pub fn reschedule_draft(ctx: Context<RescheduleDraft>) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+
+    require!(league.state == LeagueState::Setup, FflError::NotSetup);
+    require!(league.draft_slot != 0, FflError::EntropyNotRevealed);
+    require!(
+        find_slot_hash(&ctx.accounts.slot_hashes.try_borrow_data()?, league.draft_slot) == SlotHashLookup::Expired,
+        FflError::DraftSlotNotExpired
+    );
+
+    league.updated_at = Clock::get()?.unix_timestamp;
+    schedule_draft(league)
+}
+
+/// Fixes the draft slot if every remaining player has revealed, and announces it.
+fn schedule_draft(league: &mut League) -> Result<()> {
+    league.schedule_draft_slot(Clock::get()?.slot);
+    if league.draft_slot != 0 {
+        emit!(DraftSlotScheduled {
+            league_id: league.league_id,
+            draft_slot: league.draft_slot,
+        });
+    }
+    Ok(())
+}
+
+/// The hash of the league's draft slot, read straight from the SlotHashes sysvar.
+pub fn draft_slot_hash(league: &League, slot_hashes: &AccountInfo) -> Result<[u8; 32]> {
+    match find_slot_hash(&slot_hashes.try_borrow_data()?, league.draft_slot) {
+        SlotHashLookup::Found(hash) => Ok(hash),
+        SlotHashLookup::NotReached => err!(FflError::SlotHashUnavailable),
+        SlotHashLookup::Expired => err!(FflError::DraftSlotExpired),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SlotHashLookup {
+    Found([u8; 32]),
+    NotReached,
+    Expired,
+}
+
+/// Looks up `target` in raw SlotHashes data: a u64 count, then (slot u64, hash) pairs,
+/// newest first. Slots that produced no block are missing, so a skipped target resolves
+/// to the first slot after it. If the list runs out before reaching a slot older than the
+/// target, that slot may have aged out and the lookup counts as expired.
+pub fn find_slot_hash(data: &[u8], target: u64) -> SlotHashLookup {
+    const ENTRY_LEN: usize = 8 + 32;
+
+    let Some(count) = data.get(..8) else {
+        return SlotHashLookup::NotReached;
+    };
+    let count = u64::from_le_bytes(count.try_into().unwrap()) as usize;
+    let count = count.min((data.len() - 8) / ENTRY_LEN);
+
+    let mut found = None;
+    for i in 0..count {
+        let entry = &data[8 + i * ENTRY_LEN..8 + (i + 1) * ENTRY_LEN];
+        let slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if slot < target {
+            break;
+        }
+        found = Some((i, slot, <[u8; 32]>::try_from(&entry[8..]).unwrap()));
+    }
+
+    match found {
+        None => SlotHashLookup::NotReached,
+        Some((i, slot, hash)) if i + 1 < count || slot == target => SlotHashLookup::Found(hash),
+        Some(_) => SlotHashLookup::Expired,
+    }
+}
+
+pub fn mix_slot_hash(seed: &[u8; 32], slot_hash: &[u8; 32]) -> [u8; 32] {
+    hashv(&[b"draft_slot_hash", seed, slot_hash]).to_bytes()
+}
+
+/// The value a revealed secret adds to `League::draft_seed`.
+pub fn entropy_contribution(secret: &[u8; 32]) -> [u8; 32] {
+    hashv(&[b"draft_entropy", secret]).to_bytes()
+}
+
+pub fn xor_into(seed: &mut [u8; 32], contribution: &[u8; 32]) {
+    for (byte, other) in seed.iter_mut().zip(contribution.iter()) {
+        *byte ^= other;
+    }
+}
+
+/// Fisher-Yates shuffle where every swap draws fresh randomness from the seed.
+pub fn shuffle_draft_order(players: &[Pubkey], seed: &[u8; 32]) -> Vec<Pubkey> {
+    let mut draft_order = players.to_vec();
+
+    for i in (1..draft_order.len()).rev() {
+        let digest = hashv(&[seed, &(i as u64).to_le_bytes()]).to_bytes();
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&digest[..8]);
+        let j = (u64::from_le_bytes(word) % (i as u64 + 1)) as usize;
+        draft_order.swap(i, j);
+    }
+
+    draft_order
+}
+
+#[derive(Accounts)]
+pub struct RevealEntropy<'info> {
+    #[account(mut)]
+    pub league: Account<'info, League>,
+
+    #[account(
+        mut,
+        seeds = [b"player_state", league.key().as_ref(), player.key().as_ref()],
+        bump = player_state.bump
+    )]
+    pub player_state: Account<'info, PlayerState>,
+
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LockLeague<'info> {
+    #[account(
+        mut,
+        constraint = league.is_commissioner(&commissioner.key()) @ FflError::NotCommissioner
+    )]
+    pub league: Account<'info, League>,
+    pub commissioner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RescheduleDraft<'info> {
+    #[account(mut)]
+    pub league: Account<'info, League>,
+
+    /// CHECK: The SlotHashes sysvar, read raw because it is too large to deserialize
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveUnrevealed<'info> {
+    #[account(mut)]
+    pub league: Account<'info, League>,
+
+    #[account(
+        mut,
+        seeds = [b"player_state", league.key().as_ref(), player.key().as_ref()],
+        bump = player_state.bump,
+        close = player
+    )]
+    pub player_state: Account<'info, PlayerState>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_pool", league.key().as_ref()],
+        bump = league.vault_bump
+    )]
+    pub prize_pool_vault: SystemAccount<'info>,
+
+    // Token leagues only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut, seeds = [b"token_vault", league.key().as_ref()], bump)]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// CHECK: The player being removed; receives the refund and the PlayerState rent
+    #[account(mut)]
+    pub player: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot_hashes(entries: &[(u64, u8)]) -> Vec<u8> {
+        let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+        for (slot, fill) in entries {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[*fill; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn shuffle_is_a_deterministic_permutation() {
+        let players: Vec<Pubkey> = (0..MAX_PLAYERS).map(|_| Pubkey::new_unique()).collect();
+        let order = shuffle_draft_order(&players, &[7; 32]);
+
+        assert_eq!(order, shuffle_draft_order(&players, &[7; 32]));
+        assert_ne!(order, shuffle_draft_order(&players, &[8; 32]));
+
+        let mut sorted = order.clone();
+        sorted.sort();
+        let mut expected = players.clone();
+        expected.sort();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn xor_into_is_self_inverse_and_order_independent() {
+        let a = entropy_contribution(&[1; 32]);
+        let b = entropy_contribution(&[2; 32]);
+
+        let mut ab = [0u8; 32];
+        xor_into(&mut ab, &a);
+        xor_into(&mut ab, &b);
+        let mut ba = [0u8; 32];
+        xor_into(&mut ba, &b);
+        xor_into(&mut ba, &a);
+        assert_eq!(ab, ba);
+
+        xor_into(&mut ab, &b);
+        assert_eq!(ab, a);
+    }
+
+    #[test]
+    fn finds_the_target_slot_hash() {
+        let data = slot_hashes(&[(105, 5), (104, 4), (102, 2), (101, 1)]);
+
+        assert_eq!(find_slot_hash(&data, 104), SlotHashLookup::Found([4; 32]));
+        // Slot 103 was skipped, so the next produced slot stands in for it
+        assert_eq!(find_slot_hash(&data, 103), SlotHashLookup::Found([4; 32]));
+        assert_eq!(find_slot_hash(&data, 101), SlotHashLookup::Found([1; 32]));
+        assert_eq!(find_slot_hash(&data, 106), SlotHashLookup::NotReached);
+        assert_eq!(find_slot_hash(&data, 100), SlotHashLookup::Expired);
+        assert_eq!(find_slot_hash(&[], 100), SlotHashLookup::NotReached);
+    }
+
+    #[test]
+    fn count_is_clamped_to_the_data() {
+        let mut data = slot_hashes(&[(105, 5), (104, 4)]);
+        data[..8].copy_from_slice(&512u64.to_le_bytes());
+
+        assert_eq!(find_slot_hash(&data, 105), SlotHashLookup::Found([5; 32]));
+        assert_eq!(find_slot_hash(&data, 103), SlotHashLookup::Expired);
+    }
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::FflError;
+use crate::events::{MarketRegistered, MarketSettled};
+
+pub fn register_market(
+    ctx: Context<RegisterMarket>,
+    market_id: String,
+    close_time: i64,
+) -> Result<()> {
+    require!(market_id.len() <= MAX_MARKET_ID_LEN, FflError::MarketIdTooLong);
+
+    let market = &mut ctx.accounts.market;
+    market.market_id = market_id;
+    market.status = MarketStatus::Open;
+    market.close_time = close_time;
+    market.outcome = None;
+    market.final_prob = 0;
+    market.resolved_at = 0;
+    market.bump = ctx.bumps.market;
+
+    emit!(MarketRegistered {
+        market_id: market.market_id.clone(),
+        close_time,
+    });
+
+    Ok(())
+}
+
+pub fn settle_market(
+    ctx: Context<SettleMarket>,
+    _market_id: String,
+    outcome: MarketOutcome,
+    final_prob: u32,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    require!(market.status == MarketStatus::Open, FflError::MarketAlreadyResolved);
+    require!(final_prob <= 10000, FflError::InvalidProbability);
+
+    market.status = MarketStatus::Resolved;
+    market.outcome = Some(outcome);
+    market.final_prob = final_prob;
+    market.resolved_at = Clock::get()?.unix_timestamp;
+
+    emit!(MarketSettled {
+        market_id: market.market_id.clone(),
+        outcome,
+        final_prob,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct RegisterMarket<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        seeds = [b"market", market_id.as_bytes()],
+        bump,
+        payer = resolver,
+        space = Market::LEN
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        constraint = resolver.key() == config.resolver @ FflError::UnauthorizedResolver
+    )]
+    pub resolver: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct SettleMarket<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market_id.as_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(constraint = resolver.key() == config.resolver @ FflError::UnauthorizedResolver)]
+    pub resolver: Signer<'info>,
+}
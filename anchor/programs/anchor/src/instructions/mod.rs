@@ -1,12 +1,28 @@
-pub mod trades;
-
 pub mod trades;
 pub mod scoring;
 pub mod resolve;
 pub mod payout;
+pub mod config;
+pub mod market;
+pub mod session;
+pub mod commissioner;
+pub mod entropy;
+pub mod vault;
+pub mod refund;
+pub mod profile;
+pub mod draft;
 
 pub use trades::*;
 pub use scoring::*;
 pub use resolve::*;
 pub use payout::*;
+pub use config::*;
+pub use market::*;
+pub use session::*;
+pub use commissioner::*;
+pub use entropy::*;
+pub use vault::*;
+pub use refund::*;
+pub use profile::*;
+pub use draft::*;
 
@@ -1,121 +1,498 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::system_instruction;
-use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
 use crate::errors::FflError;
+use crate::events::*;
+use super::vault;
+use crate::xp::{award_xp, XP_SEASON_FINISHED};
 
-pub fn end_season(ctx: Context<EndSeason>) -> Result<()> {
+/// Ends the season and snapshots the standings.
+///
+/// `remaining_accounts` must hold every player's PlayerState (writable). Each one gets its
+/// final rank and share of the prize pool, so payouts no longer depend on live points.
+pub fn end_season<'info>(ctx: Context<'_, '_, 'info, 'info, EndSeason<'info>>) -> Result<()> {
     let league = &mut ctx.accounts.league;
-    // In production, check if all sessions are complete
+
+    require!(league.state != LeagueState::Completed, FflError::SeasonAlreadyEnded);
+    require!(
+        league.current_session == league.sessions_total && league.state == LeagueState::Active,
+        FflError::SeasonNotFinished
+    );
+    require!(league.session_resolved_count == league.session_picks_count, FflError::PicksUnresolved);
+
+    finish_season(league, ctx.remaining_accounts)
+}
+
+/// Snapshots final ranks and payout shares onto every PlayerState and completes the league.
+pub fn finish_season<'info>(league: &mut League, player_state_infos: &'info [AccountInfo<'info>]) -> Result<()> {
+    require!(player_state_infos.iter().all(|info| info.is_writable), FflError::InvalidStandingsAccounts);
+    let mut player_states = load_player_states(league, player_state_infos)?;
+    let points: Vec<i64> = player_states.iter().map(|ps| ps.points).collect();
+    let standings = rank_and_split(&league.payout_structure, &points);
+
+    for (player_state, (rank, payout_bps)) in player_states.iter_mut().zip(standings) {
+        player_state.final_rank = rank;
+        player_state.payout_bps = payout_bps;
+        award_xp(player_state, XP_SEASON_FINISHED);
+        player_state.exit(&crate::ID)?;
+    }
+
     league.state = LeagueState::Completed;
+    league.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(SeasonEnded {
+        league_id: league.league_id,
+        sessions_played: league.current_session,
+        total_points: league.total_points,
+    });
+    Ok(())
+}
+
+/// Sets aside the protocol and commissioner fees, then freezes the remaining prize pool and
+/// each player's entitlement into the Standings account. The fees stay in the vault until
+/// their recipients call `claim_fees`, so a bad fee account can't block player payouts.
+///
+/// Permissionless once the season has ended. `remaining_accounts` must hold every
+/// player's PlayerState, which carry the ranks and shares snapshotted at season end.
+pub fn finalize_standings<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeStandings<'info>>) -> Result<()> {
+    let league = &ctx.accounts.league;
+    let config = &ctx.accounts.config;
+
+    require!(league.state == LeagueState::Completed, FflError::LeagueNotCompleted);
+
+    let gross_pool = match league.mint {
+        None => vault::vault_pool_balance(&ctx.accounts.prize_pool_vault.to_account_info())?,
+        Some(_) => {
+            let token_vault = ctx.accounts.token_vault.as_ref().ok_or(FflError::MissingTokenAccounts)?;
+            token_vault.amount
+        }
+    };
+
+    let player_states = load_player_states(league, ctx.remaining_accounts)?;
+
+    // A drawn head-to-head refunds both stakes in full, so no fees are taken
+    let is_refund = league.mode == LeagueMode::HeadToHead && player_states.iter().all(|ps| ps.final_rank == 1);
+    let (protocol_fee, commissioner_fee) = if is_refund {
+        (0, 0)
+    } else {
+        (
+            (gross_pool as u128 * league.protocol_fee_bps as u128 / 10_000) as u64,
+            (gross_pool as u128 * league.commissioner_fee_bps as u128 / 10_000) as u64,
+        )
+    };
+    let total_pool = gross_pool - protocol_fee - commissioner_fee;
+
+    emit!(FeesCollected {
+        league_id: league.league_id,
+        gross_pool,
+        protocol_fee,
+        fee_recipient: config.fee_recipient,
+        commissioner_fee,
+        commissioner: league.creator,
+    });
+
+    let mut entries: Vec<StandingEntry> = player_states
+        .iter()
+        .map(|ps| StandingEntry {
+            player: ps.player,
+            rank: ps.final_rank,
+            points: ps.points,
+            payout_bps: ps.payout_bps,
+            amount: (total_pool as u128 * ps.payout_bps as u128 / 10_000) as u64,
+            claimed: false,
+            xp: ps.xp,
+        })
+        .collect();
+    entries.sort_by_key(|e| e.rank);
+
+    // Rounding dust goes to the best-ranked paid player so the vault ends up empty
+    let allocated: u64 = entries.iter().map(|e| e.amount).sum();
+    if let Some(top) = entries.iter_mut().find(|e| e.amount > 0) {
+        top.amount += total_pool - allocated;
+    }
+
+    let standings = &mut ctx.accounts.standings;
+    standings.league = league.key();
+    standings.total_pool = total_pool;
+    standings.protocol_fee = protocol_fee;
+    standings.commissioner_fee = commissioner_fee;
+    standings.fee_recipient = config.fee_recipient;
+    standings.protocol_fee_claimed = protocol_fee == 0;
+    standings.commissioner_fee_claimed = commissioner_fee == 0;
+    standings.entries = entries;
+    standings.finalized_at = Clock::get()?.unix_timestamp;
+    standings.ratings_updated = false;
+    standings.bump = ctx.bumps.standings;
+
+    Ok(())
+}
+
+/// Pays the signer whichever fees in the Standings are owed to them: the protocol fee to
+/// the fee recipient recorded at finalization, the commissioner fee to the league creator.
+pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+    let league = &ctx.accounts.league;
+    let standings = &mut ctx.accounts.standings;
+    let recipient = ctx.accounts.recipient.key();
+
+    require!(
+        recipient == standings.fee_recipient || recipient == league.creator,
+        FflError::Unauthorized
+    );
+
+    let mut amount = 0;
+    if recipient == standings.fee_recipient && !standings.protocol_fee_claimed {
+        amount += standings.protocol_fee;
+        standings.protocol_fee_claimed = true;
+    }
+    if recipient == league.creator && !standings.commissioner_fee_claimed {
+        amount += standings.commissioner_fee;
+        standings.commissioner_fee_claimed = true;
+    }
+    require!(amount > 0, FflError::AlreadyClaimed);
+
+    match league.mint {
+        None => vault::transfer_from_vault(
+            &league.key(),
+            league.vault_bump,
+            &ctx.accounts.prize_pool_vault.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            amount,
+        )?,
+        Some(_) => vault::TokenTransferAccounts::for_league(
+            league,
+            &recipient,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.recipient_token_account,
+            &ctx.accounts.token_program,
+        )?.withdraw(league, amount)?,
+    }
+
+    emit!(FeesClaimed {
+        league_id: league.league_id,
+        recipient,
+        amount,
+    });
+
     Ok(())
 }
 
 pub fn claim_payout(ctx: Context<ClaimPayout>) -> Result<()> {
     let league = &ctx.accounts.league;
+    let standings = &mut ctx.accounts.standings;
     let player_state = &mut ctx.accounts.player_state;
     let prize_pool_vault = &mut ctx.accounts.prize_pool_vault;
     let player = &mut ctx.accounts.player;
 
-    require!(league.state == LeagueState::Completed, FflError::SessionNotActive); // Reuse error or add LeagueNotCompleted
-    require!(!player_state.has_claimed, FflError::Unauthorized); // Reuse error or add AlreadyClaimed
-    
-    // Calculate payout
-    // Payout = (Player Points / Total League Points) * Prize Pool Balance
-    
-    let total_prize_pool = prize_pool_vault.lamports();
-    let player_points = if player_state.points < 0 { 0 } else { player_state.points as u64 };
-    let total_league_points = if league.total_points < 0 { 0 } else { league.total_points as u64 };
-    
-    let payout = if total_league_points > 0 {
-        (player_points as u128 * total_prize_pool as u128 / total_league_points as u128) as u64
-    } else {
-        // If no points (everyone lost?), split equally? Or refund?
-        // Let's split equally if total points is 0
-        total_prize_pool / (league.players.len() as u64)
-    };
-    
-    if payout > 0 {
-        // Transfer from Prize Pool Vault (PDA) to Player
-        let league_key = league.key();
-        let seeds = &[
-            b"prize_pool",
-            league_key.as_ref(),
-            &[league.bump], // Wait, prize_pool bump? We need to store it or derive it.
-            // In create_league, we used seeds = [b"prize_pool", league.key().as_ref()], bump
-            // We didn't store the bump in League state for the vault.
-            // We can re-derive it here using ctx.bumps if passed, or find_program_address.
-            // But invoke_signed needs the bump.
-        ];
-        
-        // Actually, we need the bump for the vault.
-        // Let's assume we can get it from the context if we added it to the struct, 
-        // or we need to store it in the League account.
-        // The League account has `bump` but that's for the league account itself.
-        // Let's check `create_league` in lib.rs.
-        // It didn't store prize_pool_bump.
-        
-        // FIX: We should store prize_pool_bump in League or pass it.
-        // For now, let's use `ctx.bumps.prize_pool_vault`.
-        
-        let bump = ctx.bumps.prize_pool_vault;
-        let signer_seeds = &[
-            b"prize_pool",
-            league_key.as_ref(),
-            &[bump],
-        ];
-        
-        // We can't use system_instruction::transfer for PDA signer easily with Anchor's transfer wrapper?
-        // Anchor doesn't have a helper for system transfer from PDA?
-        // We use invoke_signed with system_instruction::transfer.
-        
-        let ix = system_instruction::transfer(
-            &prize_pool_vault.key(),
-            &player.key(),
+    require!(league.state == LeagueState::Completed, FflError::LeagueNotCompleted);
+    require!(!player_state.has_claimed, FflError::AlreadyClaimed);
+
+    // Payout was fixed when the standings were finalized
+    let entry = standings.entries
+        .iter_mut()
+        .find(|e| e.player == player.key())
+        .ok_or(FflError::NotInStandings)?;
+    require!(!entry.claimed, FflError::AlreadyClaimed);
+    let payout = entry.amount;
+    let rank = entry.rank;
+    entry.claimed = true;
+
+    // Transfer from the league's vault to Player
+    match league.mint {
+        None => vault::transfer_from_vault(
+            &league.key(),
+            league.vault_bump,
+            &prize_pool_vault.to_account_info(),
+            &player.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
             payout,
-        );
-        
-        invoke_signed(
-            &ix,
-            &[
-                prize_pool_vault.to_account_info(),
-                player.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-            &[signer_seeds],
-        )?;
+        )?,
+        Some(_) => {
+            let token_accounts = vault::TokenTransferAccounts::for_league(
+                league,
+                &player.key(),
+                &ctx.accounts.mint,
+                &ctx.accounts.token_vault,
+                &ctx.accounts.player_token_account,
+                &ctx.accounts.token_program,
+            )?;
+            token_accounts.withdraw(league, payout)?;
+        }
     }
     
     player_state.has_claimed = true;
+
+    emit!(PayoutClaimed {
+        league_id: league.league_id,
+        player: player.key(),
+        rank,
+        amount: payout,
+    });
     
     Ok(())
 }
 
+/// Loads every player's state from `accounts`, which must cover the league exactly once.
+fn load_player_states<'info>(
+    league: &League,
+    accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<Account<'info, PlayerState>>> {
+    require!(accounts.len() == league.players.len(), FflError::InvalidStandingsAccounts);
+
+    let mut player_states: Vec<Account<'info, PlayerState>> = Vec::with_capacity(accounts.len());
+    for info in accounts {
+        let player_state = Account::<PlayerState>::try_from(info)?;
+        require!(
+            player_state.league_id == league.league_id &&
+            league.players.contains(&player_state.player) &&
+            !player_states.iter().any(|ps| ps.player == player_state.player),
+            FflError::InvalidStandingsAccounts
+        );
+        player_states.push(player_state);
+    }
+
+    Ok(player_states)
+}
+
+/// Ranks players by points and splits 10000 bps between them per the payout structure.
+///
+/// Returns `(rank, payout_bps)` for each entry of `points`, in the same order. Tied players
+/// share the best rank of their group and split the places they cover evenly. Ranked tables
+/// are rescaled over the places that exist, so a top-3 table in a 2-player league still pays
+/// out the whole pool.
+pub fn rank_and_split(structure: &PayoutStructure, points: &[i64]) -> Vec<(u8, u16)> {
+    let n = points.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|a, b| points[*b].cmp(&points[*a]));
+
+    let mut result = vec![(0u8, 0u16); n];
+    let table = structure.place_shares();
+    let table_total: u64 = table
+        .as_ref()
+        .map(|t| t.iter().take(n).map(|s| *s as u64).sum())
+        .unwrap_or(0);
+    let positive_total: u64 = points.iter().map(|p| (*p).max(0) as u64).sum();
+
+    let mut place = 0;
+    while place < n {
+        // Find the group of players tied with the one at `place`
+        let mut group_end = place + 1;
+        while group_end < n && points[order[group_end]] == points[order[place]] {
+            group_end += 1;
+        }
+        let group_size = (group_end - place) as u64;
+
+        for &index in &order[place..group_end] {
+            let payout_bps = match &table {
+                Some(table) => {
+                    let group_share: u64 = table
+                        .iter()
+                        .take(group_end)
+                        .skip(place)
+                        .map(|s| *s as u64)
+                        .sum();
+                    group_share * 10_000 / (table_total * group_size)
+                }
+                None if positive_total > 0 => points[index].max(0) as u64 * 10_000 / positive_total,
+                // Nobody scored: split evenly
+                None => 10_000 / n as u64,
+            };
+            result[index] = ((place + 1) as u8, payout_bps as u16);
+        }
+
+        place = group_end;
+    }
+
+    result
+}
+
 #[derive(Accounts)]
 pub struct EndSeason<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        constraint = league.is_season_authority(&authority.key(), &config) @ FflError::NotCommissioner
+    )]
+    pub league: Account<'info, League>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeStandings<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub league: Account<'info, League>,
+
+    #[account(
+        init,
+        seeds = [b"standings", league.key().as_ref()],
+        bump,
+        payer = payer,
+        space = Standings::space(league.players.len())
+    )]
+    pub standings: Account<'info, Standings>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_pool", league.key().as_ref()],
+        bump = league.vault_bump
+    )]
+    pub prize_pool_vault: SystemAccount<'info>,
+
+    // Token leagues only
+    #[account(seeds = [b"token_vault", league.key().as_ref()], bump)]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
     #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
     pub league: Account<'info, League>,
+
+    #[account(
+        mut,
+        seeds = [b"standings", league.key().as_ref()],
+        bump = standings.bump
+    )]
+    pub standings: Account<'info, Standings>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_pool", league.key().as_ref()],
+        bump = league.vault_bump
+    )]
+    pub prize_pool_vault: SystemAccount<'info>,
+
+    // Token leagues only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut, seeds = [b"token_vault", league.key().as_ref()], bump)]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub recipient: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct ClaimPayout<'info> {
     #[account(mut)]
     pub league: Account<'info, League>,
+
+    #[account(
+        mut,
+        seeds = [b"standings", league.key().as_ref()],
+        bump = standings.bump
+    )]
+    pub standings: Account<'info, Standings>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"player_state", league.key().as_ref(), player.key().as_ref()],
+        bump = player_state.bump
+    )]
     pub player_state: Account<'info, PlayerState>,
     
     #[account(
         mut,
         seeds = [b"prize_pool", league.key().as_ref()],
-        bump
+        bump = league.vault_bump
     )]
     pub prize_pool_vault: SystemAccount<'info>, // Use SystemAccount for the vault (it's just a PDA with SOL)
+
+    // Token leagues only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut, seeds = [b"token_vault", league.key().as_ref()], bump)]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     
     #[account(mut)]
     pub player: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total_bps(result: &[(u8, u16)]) -> u64 {
+        result.iter().map(|(_, bps)| *bps as u64).sum()
+    }
+
+    #[test]
+    fn winner_take_all_pays_the_top_scorer() {
+        let result = rank_and_split(&PayoutStructure::WinnerTakeAll, &[10, 30, 20]);
+        assert_eq!(result, vec![(3, 0), (1, 10_000), (2, 0)]);
+    }
+
+    #[test]
+    fn tied_players_share_rank_and_places() {
+        let result = rank_and_split(&PayoutStructure::WinnerTakeAll, &[30, 30, 10]);
+        assert_eq!(result, vec![(1, 5_000), (1, 5_000), (3, 0)]);
+
+        let result = rank_and_split(&PayoutStructure::TopThree, &[50, 20, 20, 0]);
+        assert_eq!(result, vec![(1, 6_000), (2, 2_000), (2, 2_000), (4, 0)]);
+    }
+
+    #[test]
+    fn ranked_table_is_rescaled_over_existing_places() {
+        let result = rank_and_split(&PayoutStructure::TopThree, &[5, 9]);
+        assert_eq!(result, vec![(2, 3_333), (1, 6_666)]);
+    }
+
+    #[test]
+    fn proportional_splits_by_positive_points() {
+        let result = rank_and_split(&PayoutStructure::Proportional, &[30, 10, -5]);
+        assert_eq!(result, vec![(1, 7_500), (2, 2_500), (3, 0)]);
+    }
+
+    #[test]
+    fn proportional_splits_evenly_when_nobody_scored() {
+        let result = rank_and_split(&PayoutStructure::Proportional, &[0, 0, 0, 0]);
+        assert_eq!(result, vec![(1, 2_500); 4]);
+
+        let result = rank_and_split(&PayoutStructure::Proportional, &[-3, -1]);
+        assert_eq!(result, vec![(2, 5_000), (1, 5_000)]);
+    }
+
+    #[test]
+    fn shares_never_exceed_the_pool() {
+        let structures = [
+            PayoutStructure::WinnerTakeAll,
+            PayoutStructure::TopThree,
+            PayoutStructure::Proportional,
+            PayoutStructure::Custom(vec![5_000, 3_000, 2_000]),
+            PayoutStructure::Custom(vec![3_334, 3_333, 3_333]),
+        ];
+        let tables: [&[i64]; 7] = [
+            &[7],
+            &[1, 2],
+            &[3, 3, 3],
+            &[10, 9, 9, 9, 1, 0, -4],
+            &[-1, -1, -1, 5, 5, 5, 5, 5, 5, 5, 5],
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+            &[0; 16],
+        ];
+
+        for structure in &structures {
+            for points in tables {
+                let result = rank_and_split(structure, points);
+                let total = total_bps(&result);
+                assert!(total <= 10_000, "{} bps for {:?}", total, points);
+                // Truncation loses at most one bps per player
+                assert!(total + points.len() as u64 >= 10_000, "{} bps for {:?}", total, points);
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::FflError;
+use crate::events::ProfileUpdated;
+
+pub const ELO_K_FACTOR: i64 = 32;
+
+// Expected score (bps) of the higher-rated player, for rating gaps of 0, 25, ..., 800.
+// Gaps beyond 800 use the last entry.
+const EXPECTED_SCORE_BPS: [i64; 33] = [
+    5000, 5359, 5715, 6063, 6401, 6725, 7034, 7325, 7597, 7850, 8083, 8296, 8490, 8666, 8823, 8965,
+    9091, 9203, 9302, 9390, 9468, 9536, 9595, 9648, 9693, 9733, 9768, 9799, 9825, 9848, 9868, 9886,
+    9901,
+];
+
+/// Applies a completed league's frozen standings to every player's Profile, including the
+/// XP each player earned in the league.
+///
+/// Permissionless, and only once per league. `remaining_accounts` must hold each player's
+/// Profile (writable) in the same order as `standings.entries`. A head-to-head league is a
+/// plain ELO match; larger leagues score each player against every opponent by placement
+/// and average the result.
+pub fn update_ratings<'info>(ctx: Context<'_, '_, 'info, 'info, UpdateRatings<'info>>) -> Result<()> {
+    let standings = &mut ctx.accounts.standings;
+
+    require!(ctx.accounts.league.state == LeagueState::Completed, FflError::LeagueNotCompleted);
+    require!(!standings.ratings_updated, FflError::RatingsAlreadyUpdated);
+    require!(
+        ctx.remaining_accounts.len() == standings.entries.len() &&
+        ctx.remaining_accounts.iter().all(|info| info.is_writable),
+        FflError::InvalidProfileAccounts
+    );
+
+    let mut profiles: Vec<Account<'info, Profile>> = Vec::with_capacity(standings.entries.len());
+    for (info, entry) in ctx.remaining_accounts.iter().zip(&standings.entries) {
+        let profile = Account::<Profile>::try_from(info)?;
+        require!(profile.wallet == entry.player, FflError::InvalidProfileAccounts);
+        profiles.push(profile);
+    }
+
+    let ratings: Vec<u16> = profiles.iter().map(|p| p.rating).collect();
+    let ranks: Vec<u8> = standings.entries.iter().map(|e| e.rank).collect();
+    let deltas = rating_deltas(&ratings, &ranks);
+    let winners = ranks.iter().filter(|r| **r == 1).count();
+
+    for ((profile, entry), delta) in profiles.iter_mut().zip(&standings.entries).zip(deltas) {
+        profile.rating = (profile.rating as i64 + delta).clamp(0, u16::MAX as i64) as u16;
+        match (entry.rank, winners) {
+            (1, 1) => profile.wins += 1,
+            (1, _) => profile.draws += 1,
+            _ => profile.losses += 1,
+        }
+        profile.leagues_played += 1;
+        profile.xp += entry.xp as u64;
+        profile.exit(&crate::ID)?;
+
+        emit!(ProfileUpdated {
+            wallet: profile.wallet,
+            league_id: ctx.accounts.league.league_id,
+            rating: profile.rating,
+            rating_change: delta as i32,
+            xp: profile.xp,
+            level: profile.level(),
+        });
+    }
+
+    standings.ratings_updated = true;
+    Ok(())
+}
+
+/// Expected score (bps) of a player rated `rating` against one rated `opponent`.
+pub fn expected_score_bps(rating: u16, opponent: u16) -> i64 {
+    let gap = (rating as i64 - opponent as i64).abs();
+    let expected = EXPECTED_SCORE_BPS[((gap / 25) as usize).min(EXPECTED_SCORE_BPS.len() - 1)];
+    if rating >= opponent { expected } else { 10_000 - expected }
+}
+
+/// Rating change for each player, treating every pair of players as one match decided by
+/// rank (lower wins, equal draws). Each player's change is averaged over their opponents,
+/// so two-player leagues get standard ELO.
+pub fn rating_deltas(ratings: &[u16], ranks: &[u8]) -> Vec<i64> {
+    let opponents = ratings.len() as i64 - 1;
+    if opponents < 1 {
+        return vec![0; ratings.len()];
+    }
+
+    (0..ratings.len())
+        .map(|i| {
+            let total: i64 = (0..ratings.len())
+                .filter(|j| *j != i)
+                .map(|j| {
+                    let score = match ranks[i].cmp(&ranks[j]) {
+                        std::cmp::Ordering::Less => 10_000,
+                        std::cmp::Ordering::Equal => 5_000,
+                        std::cmp::Ordering::Greater => 0,
+                    };
+                    ELO_K_FACTOR * (score - expected_score_bps(ratings[i], ratings[j]))
+                })
+                .sum();
+            total / (opponents * 10_000)
+        })
+        .collect()
+}
+
+#[derive(Accounts)]
+pub struct UpdateRatings<'info> {
+    pub league: Account<'info, League>,
+
+    #[account(
+        mut,
+        seeds = [b"standings", league.key().as_ref()],
+        bump = standings.bump
+    )]
+    pub standings: Account<'info, Standings>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_scores_are_symmetric() {
+        for (a, b) in [(1200, 1200), (1200, 1400), (1000, 1013), (900, 2000)] {
+            assert_eq!(expected_score_bps(a, b) + expected_score_bps(b, a), 10_000);
+        }
+        assert_eq!(expected_score_bps(1400, 1200), 7_597);
+        // Gaps beyond 800 use the last entry
+        assert_eq!(expected_score_bps(2500, 1200), 9_901);
+    }
+
+    #[test]
+    fn head_to_head_is_plain_elo() {
+        assert_eq!(rating_deltas(&[1200, 1200], &[1, 2]), vec![16, -16]);
+        assert_eq!(rating_deltas(&[1200, 1200], &[1, 1]), vec![0, 0]);
+        // An upset moves both ratings by the same amount
+        assert_eq!(rating_deltas(&[1200, 1400], &[1, 2]), vec![24, -24]);
+        assert_eq!(rating_deltas(&[1200, 1400], &[2, 1]), vec![-7, 7]);
+    }
+
+    #[test]
+    fn placements_average_over_opponents() {
+        assert_eq!(rating_deltas(&[1200, 1200, 1200], &[1, 2, 3]), vec![16, 0, -16]);
+        assert_eq!(rating_deltas(&[1200, 1200, 1200], &[1, 1, 3]), vec![8, 8, -16]);
+    }
+
+    #[test]
+    fn single_player_is_unrated() {
+        assert_eq!(rating_deltas(&[1200], &[1]), vec![0]);
+    }
+}
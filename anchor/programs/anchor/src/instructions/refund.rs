@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::FflError;
+use crate::events::*;
+use super::vault;
+
+/// Cancels a league that never started its draft. Commissioners can cancel at any time
+/// during Setup; anyone can once the league's setup deadline has passed, or once the
+/// reveal window has closed with too few players left.
+pub fn cancel_league(ctx: Context<CancelLeague>) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(league.state == LeagueState::Setup, FflError::NotSetup);
+    // Also cancellable by anyone once removing non-revealers left too few players to draft
+    let stranded = league.is_locked() &&
+        now > league.reveal_deadline &&
+        league.players.len() < league.min_players as usize;
+    require!(
+        league.is_commissioner(&ctx.accounts.authority.key()) ||
+        (league.setup_deadline != 0 && now >= league.setup_deadline) ||
+        stranded,
+        FflError::CancelNotAllowed
+    );
+
+    league.state = LeagueState::Cancelled;
+    league.updated_at = now;
+
+    emit!(LeagueCancelled {
+        league_id: league.league_id,
+        cancelled_by: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
+
+/// Returns a player's buy-in from a cancelled league and closes their PlayerState.
+pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+    let league = &ctx.accounts.league;
+    require!(league.state == LeagueState::Cancelled, FflError::LeagueNotCancelled);
+
+    emit!(RefundClaimed {
+        league_id: league.league_id,
+        player: ctx.accounts.player.key(),
+        amount: league.buy_in_amount,
+    });
+
+    refund_buy_in(
+        &ctx.accounts.league,
+        &ctx.accounts.player.to_account_info(),
+        &ctx.accounts.prize_pool_vault,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.player_token_account,
+        &ctx.accounts.token_program,
+        &ctx.accounts.system_program,
+    )
+}
+
+/// Removes a player from a league that is still open to joins, refunds their buy-in and
+/// closes their PlayerState.
+pub fn leave_league(ctx: Context<LeaveLeague>) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+    let player_state = &ctx.accounts.player_state;
+    let player = ctx.accounts.player.key();
+
+    require!(league.state == LeagueState::Setup, FflError::NotSetup);
+    // Leaving and rejoining after the reveal phase opens would let a player re-roll the draft order
+    require!(!league.is_locked() && !player_state.entropy_revealed, FflError::LeagueLocked);
+
+    let index = league.players
+        .iter()
+        .position(|key| *key == player)
+        .ok_or(FflError::NotInLeague)?;
+    league.players.remove(index);
+    league.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(PlayerLeft {
+        league_id: league.league_id,
+        player,
+        player_count: league.players.len() as u8,
+        refund: league.buy_in_amount,
+    });
+
+    refund_buy_in(
+        &ctx.accounts.league,
+        &ctx.accounts.player.to_account_info(),
+        &ctx.accounts.prize_pool_vault,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.player_token_account,
+        &ctx.accounts.token_program,
+        &ctx.accounts.system_program,
+    )
+}
+
+/// Pays `league.buy_in_amount` back to `player` from the league's vault.
+#[allow(clippy::too_many_arguments)]
+pub fn refund_buy_in<'info>(
+    league: &Account<'info, League>,
+    player: &AccountInfo<'info>,
+    prize_pool_vault: &SystemAccount<'info>,
+    mint: &Option<InterfaceAccount<'info, Mint>>,
+    token_vault: &Option<InterfaceAccount<'info, TokenAccount>>,
+    player_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+    token_program: &Option<Interface<'info, TokenInterface>>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    match league.mint {
+        None => vault::transfer_from_vault(
+            &league.key(),
+            league.vault_bump,
+            &prize_pool_vault.to_account_info(),
+            player,
+            &system_program.to_account_info(),
+            league.buy_in_amount,
+        ),
+        Some(_) => vault::TokenTransferAccounts::for_league(
+            league,
+            &player.key(),
+            mint,
+            token_vault,
+            player_token_account,
+            token_program,
+        )?.withdraw(league, league.buy_in_amount),
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelLeague<'info> {
+    #[account(mut)]
+    pub league: Account<'info, League>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    pub league: Account<'info, League>,
+
+    // Closing the account returns its rent and prevents a second refund
+    #[account(
+        mut,
+        seeds = [b"player_state", league.key().as_ref(), player.key().as_ref()],
+        bump = player_state.bump,
+        close = player
+    )]
+    pub player_state: Account<'info, PlayerState>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_pool", league.key().as_ref()],
+        bump = league.vault_bump
+    )]
+    pub prize_pool_vault: SystemAccount<'info>,
+
+    // Token leagues only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut, seeds = [b"token_vault", league.key().as_ref()], bump)]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveLeague<'info> {
+    #[account(mut)]
+    pub league: Account<'info, League>,
+
+    #[account(
+        mut,
+        seeds = [b"player_state", league.key().as_ref(), player.key().as_ref()],
+        bump = player_state.bump,
+        close = player
+    )]
+    pub player_state: Account<'info, PlayerState>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_pool", league.key().as_ref()],
+        bump = league.vault_bump
+    )]
+    pub prize_pool_vault: SystemAccount<'info>,
+
+    // Token leagues only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut, seeds = [b"token_vault", league.key().as_ref()], bump)]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
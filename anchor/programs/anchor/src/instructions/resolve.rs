@@ -1,88 +1,220 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::FflError;
+use crate::events::*;
 use super::scoring::*;
+use crate::xp::*;
 
 pub fn resolve_market(
     ctx: Context<ResolveMarket>,
     _market_id: String,
-    outcome: bool,
-    final_prob: u32, // Not used in formula but good for records?
 ) -> Result<()> {
+    let market = &ctx.accounts.market;
     let draft_pick = &mut ctx.accounts.draft_pick;
     let player_state = &mut ctx.accounts.player_state;
     let league = &mut ctx.accounts.league;
 
-    require!(!draft_pick.resolved, FflError::Unauthorized); // Should be "AlreadyResolved" but reusing Unauthorized for now or add new error
+    require!(!draft_pick.resolved, FflError::PickResolved);
+    require!(draft_pick.market_id == market.market_id, FflError::MarketMismatch);
 
-    let is_correct = (draft_pick.prediction == Prediction::Yes && outcome) ||
-                     (draft_pick.prediction == Prediction::No && !outcome);
+    // The outcome comes from the canonical market record, never from the caller
+    let outcome = market.settled_outcome()?;
+    apply_resolution(league, draft_pick, player_state, outcome);
+    emit_pick_resolved(draft_pick);
 
-    // Calculate p_pred (probability of the PREDICTED outcome at draft time)
-    let p_pred = if draft_pick.prediction == Prediction::Yes {
-        draft_pick.snapshot_odds as f64 / 10000.0
-    } else {
-        1.0 - (draft_pick.snapshot_odds as f64 / 10000.0)
-    };
+    Ok(())
+}
 
-    let mut points_change: i64 = 0;
-    let mut bonuses_earned: u32 = 0;
+/// Resolves every pick on one market in a single transaction.
+///
+/// `remaining_accounts` holds (League, DraftPick, PlayerState) triples, all writable.
+/// Picks that are already resolved are skipped rather than failing the batch, so a
+/// large set of picks can be split across several transactions and safely retried.
+pub fn batch_resolve_market<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchResolveMarket<'info>>,
+    _market_id: String,
+) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let config = &ctx.accounts.config;
+    let signer = ctx.accounts.signer.key();
+    let outcome = market.settled_outcome()?;
 
-    if is_correct {
-        points_change = calculate_win_points(p_pred);
-        
-        let session_idx = draft_pick.session_index as usize;
-        let current_wins = if session_idx < player_state.session_stats.len() {
-            player_state.session_stats[session_idx].wins
-        } else {
-            0
-        };
-
-        let (bonus_pts, bonus_val) = calculate_bonuses(
-            p_pred, 
-            true, 
-            player_state.streak, 
-            current_wins, 
-            league.rounds_per_session
+    let remaining = ctx.remaining_accounts;
+    require!(!remaining.is_empty() && remaining.len() % 3 == 0, FflError::InvalidBatchAccounts);
+
+    let mut resolved: u16 = 0;
+    let mut skipped: u16 = 0;
+
+    for triple in remaining.chunks_exact(3) {
+        let (league_info, pick_info, player_state_info) = (&triple[0], &triple[1], &triple[2]);
+        require!(
+            league_info.is_writable && pick_info.is_writable && player_state_info.is_writable,
+            FflError::InvalidBatchAccounts
+        );
+
+        let mut league = Account::<League>::try_from(league_info)?;
+        let mut draft_pick = Account::<DraftPick>::try_from(pick_info)?;
+        let mut player_state = Account::<PlayerState>::try_from(player_state_info)?;
+
+        require!(signer == league.resolver_authority(config), FflError::UnauthorizedResolver);
+        require!(draft_pick.market_id == market.market_id, FflError::MarketMismatch);
+        require!(draft_pick.league_id == league.league_id, FflError::PickNotFound);
+        require!(
+            player_state.league_id == league.league_id && player_state.player == draft_pick.player,
+            FflError::InvalidBatchAccounts
         );
-        
-        points_change += bonus_pts;
-        bonuses_earned = bonus_val;
 
+        if draft_pick.resolved {
+            skipped += 1;
+            continue;
+        }
+
+        apply_resolution(&mut league, &mut draft_pick, &mut player_state, outcome);
+        emit_pick_resolved(&draft_pick);
+
+        // Persist now so later triples touching the same league or player see the update
+        league.exit(&crate::ID)?;
+        draft_pick.exit(&crate::ID)?;
+        player_state.exit(&crate::ID)?;
+        resolved += 1;
+    }
+
+    emit!(BatchResolved {
+        market_id: market.market_id.clone(),
+        resolved,
+        skipped,
+    });
+
+    Ok(())
+}
+
+/// Scores a single pick against the market outcome and updates player and league totals.
+fn apply_resolution(
+    league: &mut League,
+    draft_pick: &mut DraftPick,
+    player_state: &mut PlayerState,
+    outcome: MarketOutcome,
+) {
+    let session_idx = draft_pick.session_index as usize;
+    if draft_pick.session_index == league.current_session {
+        league.session_resolved_count += 1;
+    }
+
+    // A voided market scores nothing and leaves the streak untouched
+    if outcome == MarketOutcome::Invalid {
+        if session_idx < player_state.session_stats.len() {
+            player_state.session_stats[session_idx].voids += 1;
+        }
+        draft_pick.resolved = true;
+        draft_pick.outcome = PickOutcome::Voided;
+        draft_pick.final_points = 0;
+        draft_pick.breakdown = PointsBreakdown::default();
+        return;
+    }
+
+    let is_correct = (draft_pick.prediction == Prediction::Yes && outcome == MarketOutcome::Yes) ||
+                     (draft_pick.prediction == Prediction::No && outcome == MarketOutcome::No);
+
+    // Probability of the PREDICTED outcome at draft time, in basis points
+    let p_pred = predicted_prob_bps(draft_pick.prediction, draft_pick.snapshot_odds);
+
+    let session_wins = if session_idx < player_state.session_stats.len() {
+        player_state.session_stats[session_idx].wins
+    } else {
+        0
+    };
+    let breakdown = score_pick(
+        &league.scoring,
+        p_pred,
+        is_correct,
+        player_state.streak,
+        session_wins,
+        league.rounds_per_session,
+    );
+    let points_change = breakdown.total();
+
+    if is_correct {
+        let mut xp = XP_CORRECT_CALL;
+        if p_pred < XP_LONGSHOT_MAX_PROB_BPS {
+            xp += XP_LONGSHOT_HIT;
+        }
+        if draft_pick.traded {
+            xp += XP_TRADE_WON;
+        }
+        award_xp(player_state, xp);
         player_state.streak += 1;
-        
         if session_idx < player_state.session_stats.len() {
             player_state.session_stats[session_idx].wins += 1;
         }
     } else {
-        points_change = calculate_loss_points(p_pred);
         player_state.streak = 0;
-        
-        let session_idx = draft_pick.session_index as usize;
         if session_idx < player_state.session_stats.len() {
             player_state.session_stats[session_idx].losses += 1;
         }
     }
 
     player_state.points += points_change;
-    player_state.bonuses += bonuses_earned;
+    player_state.bonuses += breakdown.bonus_points();
+    player_state.bonus_totals.add(&breakdown);
     league.total_points += points_change;
     
     draft_pick.resolved = true;
+    draft_pick.outcome = if is_correct { PickOutcome::Won } else { PickOutcome::Lost };
     draft_pick.final_points = points_change as i32;
+    draft_pick.breakdown = breakdown;
+}
 
-    Ok(())
+fn emit_pick_resolved(draft_pick: &Account<DraftPick>) {
+    emit!(PickResolved {
+        league_id: draft_pick.league_id,
+        draft_pick: draft_pick.key(),
+        player: draft_pick.player,
+        market_id: draft_pick.market_id.clone(),
+        outcome: draft_pick.outcome,
+        points: draft_pick.final_points,
+        breakdown: draft_pick.breakdown,
+    });
 }
 
 #[derive(Accounts)]
 #[instruction(market_id: String)]
 pub struct ResolveMarket<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [b"market", market_id.as_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
     #[account(mut)]
     pub league: Account<'info, League>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = draft_pick.league_id == league.league_id @ FflError::PickNotFound
+    )]
     pub draft_pick: Account<'info, DraftPick>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"player_state", league.key().as_ref(), draft_pick.player.as_ref()],
+        bump = player_state.bump
+    )]
     pub player_state: Account<'info, PlayerState>,
-    #[account(mut)]
-    pub signer: Signer<'info>, 
+    #[account(
+        mut,
+        constraint = signer.key() == league.resolver_authority(&config) @ FflError::UnauthorizedResolver
+    )]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct BatchResolveMarket<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [b"market", market_id.as_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    pub signer: Signer<'info>,
 }
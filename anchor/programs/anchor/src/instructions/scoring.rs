@@ -1,57 +1,129 @@
-use anchor_lang::prelude::*;
+use crate::state::{PointsBreakdown, Prediction, ScoringRules};
 
-pub fn calculate_multiplier(p_pred: f64) -> f64 {
-    if p_pred >= 0.70 {
-        1.0
-    } else if p_pred >= 0.40 {
-        1.2
+// All probabilities and multipliers are fixed-point basis points (10000 = 1.0).
+// Every division truncates toward zero, matching `calculateFinalPointsBps` in web/lib/scoring.ts.
+pub const BPS: u32 = 10_000;
+
+/// Probability of the predicted outcome at draft time, from the YES snapshot odds.
+pub fn predicted_prob_bps(prediction: Prediction, snapshot_odds: u32) -> u32 {
+    let yes_bps = snapshot_odds.min(BPS);
+    match prediction {
+        Prediction::Yes => yes_bps,
+        Prediction::No => BPS - yes_bps,
+    }
+}
+
+pub fn calculate_multiplier(rules: &ScoringRules, p_pred_bps: u32) -> u32 {
+    if p_pred_bps >= rules.favorite_threshold_bps as u32 {
+        rules.favorite_multiplier_bps as u32
+    } else if p_pred_bps >= rules.balanced_threshold_bps as u32 {
+        rules.balanced_multiplier_bps as u32
     } else {
-        1.5
+        rules.longshot_multiplier_bps as u32
     }
 }
 
-pub fn calculate_win_points(p_pred: f64) -> i64 {
-    let base = 100.0 * (1.0 - p_pred);
-    let multiplier = calculate_multiplier(p_pred);
-    (base * multiplier) as i64
+pub fn calculate_win_points(rules: &ScoringRules, p_pred_bps: u32) -> i64 {
+    // 100 * (1 - p_pred) * multiplier
+    let base = 100 * (BPS - p_pred_bps) as u64;
+    let multiplier = calculate_multiplier(rules, p_pred_bps) as u64;
+    (base * multiplier / (BPS as u64 * BPS as u64)) as i64
 }
 
-pub fn calculate_loss_points(p_pred: f64) -> i64 {
-    (-30.0 * p_pred) as i64
+pub fn calculate_loss_points(rules: &ScoringRules, p_pred_bps: u32) -> i64 {
+    // -loss_factor * p_pred
+    -(rules.loss_factor as i64 * p_pred_bps as i64 / BPS as i64)
 }
 
-pub fn calculate_bonuses(
-    p_pred: f64,
+/// Scores a resolved (non-void) pick.
+///
+/// `current_streak` and `session_wins` are the player's counts BEFORE this pick.
+pub fn score_pick(
+    rules: &ScoringRules,
+    p_pred_bps: u32,
     is_correct: bool,
     current_streak: u16,
     session_wins: u8,
     rounds_per_session: u8,
-) -> (i64, u32) {
-    let mut bonus_points: i64 = 0;
-    let mut bonus_tracker: u32 = 0;
-
-    if is_correct {
-        // Long-shot bonus
-        if p_pred < 0.20 {
-            bonus_points += 10;
-            bonus_tracker += 10;
-        }
+) -> PointsBreakdown {
+    if !is_correct {
+        return PointsBreakdown {
+            weighted_points: calculate_loss_points(rules, p_pred_bps) as i32,
+            ..Default::default()
+        };
+    }
 
-        // Streak bonus (every 5)
-        // Note: current_streak is the streak BEFORE this win. So new streak is current_streak + 1.
-        let new_streak = current_streak + 1;
-        if new_streak % 5 == 0 {
-            bonus_points += 25;
-            bonus_tracker += 25;
-        }
+    let mut breakdown = PointsBreakdown {
+        weighted_points: calculate_win_points(rules, p_pred_bps) as i32,
+        multiplier_bps: calculate_multiplier(rules, p_pred_bps) as u16,
+        ..Default::default()
+    };
+
+    // Long-shot bonus
+    if p_pred_bps < rules.longshot_bonus_threshold_bps as u32 {
+        breakdown.longshot_bonus = rules.longshot_bonus;
+    }
+
+    // Streak bonus (every `streak_interval` consecutive wins, counting this one)
+    if (current_streak + 1) % rules.streak_interval == 0 {
+        breakdown.streak_bonus = rules.streak_bonus;
+    }
+
+    // Clean sweep bonus, when this win completes a perfect session
+    if session_wins + 1 == rounds_per_session {
+        breakdown.clean_sweep_bonus = rules.clean_sweep_bonus;
+    }
+
+    breakdown
+}
 
-        // Clean sweep bonus
-        // session_wins is wins BEFORE this one. So new wins = session_wins + 1.
-        if session_wins + 1 == rounds_per_session {
-            bonus_points += 50;
-            bonus_tracker += 50;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (p_pred_bps, points if correct, points if wrong), produced by running
+    // `calculateFinalPointsBps` from web/lib/scoring.ts with STANDARD_SCORING_RULES.
+    // Regenerate this table whenever either implementation changes.
+    const WEB_FIXTURES: [(u32, i64, i64); 14] = [
+        (0, 150, 0),
+        (1, 149, 0),
+        (1999, 120, -5),
+        (2000, 120, -6),
+        (2500, 112, -7),
+        (3333, 100, -9),
+        (3999, 90, -11),
+        (4000, 72, -12),
+        (5000, 60, -15),
+        (6999, 36, -20),
+        (7000, 30, -21),
+        (8500, 15, -25),
+        (9999, 0, -29),
+        (10000, 0, -30),
+    ];
+
+    #[test]
+    fn matches_web_scoring() {
+        let rules = ScoringRules::default();
+        for (p_pred_bps, win, loss) in WEB_FIXTURES {
+            assert_eq!(calculate_win_points(&rules, p_pred_bps), win, "win at {} bps", p_pred_bps);
+            assert_eq!(calculate_loss_points(&rules, p_pred_bps), loss, "loss at {} bps", p_pred_bps);
         }
     }
 
-    (bonus_points, bonus_tracker)
+    #[test]
+    fn multiplier_tiers_start_at_their_thresholds() {
+        let rules = ScoringRules::default();
+        assert_eq!(calculate_multiplier(&rules, 7_000), 10_000);
+        assert_eq!(calculate_multiplier(&rules, 6_999), 12_000);
+        assert_eq!(calculate_multiplier(&rules, 4_000), 12_000);
+        assert_eq!(calculate_multiplier(&rules, 3_999), 15_000);
+    }
+
+    #[test]
+    fn predicted_prob_is_taken_from_the_yes_odds() {
+        assert_eq!(predicted_prob_bps(Prediction::Yes, 3_000), 3_000);
+        assert_eq!(predicted_prob_bps(Prediction::No, 3_000), 7_000);
+        // Odds above 100% are clamped
+        assert_eq!(predicted_prob_bps(Prediction::No, 12_000), 0);
+    }
 }
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::FflError;
+use crate::events::DraftStarted;
+use super::payout::finish_season;
+
+/// Moves the league to the next session once the current one is fully resolved.
+///
+/// After the last session this ends the season instead, and `remaining_accounts` must hold
+/// every player's PlayerState, as for `end_season`.
+pub fn advance_session<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AdvanceSession<'info>>,
+    rotate_draft_order: bool,
+) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+
+    require!(league.is_session_complete(), FflError::SessionNotComplete);
+
+    if league.current_session >= league.sessions_total {
+        // Last session is done, the season is over
+        return finish_season(league, ctx.remaining_accounts);
+    }
+
+    league.current_session += 1;
+    league.session_picks_count = 0;
+    league.session_resolved_count = 0;
+    league.state = LeagueState::Drafting;
+
+    // Move the first pick to the back so the same player doesn't always pick first
+    if rotate_draft_order && !league.draft_order.is_empty() {
+        league.draft_order.rotate_left(1);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    league.start_pick_clock(now);
+    league.updated_at = now;
+
+    emit!(DraftStarted {
+        league_id: league.league_id,
+        session: league.current_session,
+        draft_order: league.draft_order.clone(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdvanceSession<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        constraint = league.is_season_authority(&authority.key(), &config) @ FflError::NotCommissioner
+    )]
+    pub league: Account<'info, League>,
+    pub authority: Signer<'info>,
+}
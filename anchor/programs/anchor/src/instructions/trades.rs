@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::FflError;
+use crate::events::*;
 
 pub fn propose_trade(
     ctx: Context<ProposeTrade>,
@@ -34,6 +35,16 @@ pub fn propose_trade(
     trade_proposal.expires_at = Clock::get()?.unix_timestamp + 86400; // 24 hours
     trade_proposal.bump = ctx.bumps.trade_proposal;
 
+    emit!(TradeProposed {
+        league_id: trade_proposal.league_id,
+        trade_id,
+        proposer: trade_proposal.proposer,
+        receiver: trade_proposal.receiver,
+        proposer_pick: trade_proposal.proposer_pick,
+        receiver_pick: trade_proposal.receiver_pick,
+        expires_at: trade_proposal.expires_at,
+    });
+
     Ok(())
 }
 
@@ -78,10 +89,24 @@ pub fn respond_to_trade(
 
         proposer_pick.player = receiver;
         receiver_pick.player = proposer;
+        proposer_pick.traded = true;
+        receiver_pick.traded = true;
 
         trade_proposal.status = TradeStatus::Accepted;
+        emit!(TradeAccepted {
+            league_id: trade_proposal.league_id,
+            trade_id: trade_proposal.trade_id,
+            proposer,
+            receiver,
+        });
     } else {
         trade_proposal.status = TradeStatus::Rejected;
+        emit!(TradeRejected {
+            league_id: trade_proposal.league_id,
+            trade_id: trade_proposal.trade_id,
+            proposer: trade_proposal.proposer,
+            receiver: trade_proposal.receiver,
+        });
     }
 
     Ok(())
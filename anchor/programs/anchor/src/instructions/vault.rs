@@ -0,0 +1,257 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+};
+use crate::state::{League, LeagueState};
+use crate::errors::FflError;
+use crate::events::LeagueCurrencySet;
+
+// SOL leagues escrow lamports in the prize pool vault; token leagues (League::mint set)
+// escrow in a token account at [b"token_vault", league] whose authority is the league PDA.
+//
+// The prize pool vault is a system-owned PDA ([b"prize_pool", league]) that only holds SOL.
+// create_league funds it with the rent-exempt minimum so buy-ins of any size can land in it
+// and payouts never leave it below rent exemption.
+
+/// Lamports kept in the vault for rent exemption; never paid out.
+pub fn vault_rent_reserve() -> Result<u64> {
+    Ok(Rent::get()?.minimum_balance(0))
+}
+
+/// Lamports in the vault that belong to the prize pool.
+pub fn vault_pool_balance(vault: &AccountInfo) -> Result<u64> {
+    Ok(vault.lamports().saturating_sub(vault_rent_reserve()?))
+}
+
+pub fn transfer_to_vault<'info>(
+    from: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let ix = system_instruction::transfer(&from.key(), &vault.key(), amount);
+    invoke(&ix, &[from.clone(), vault.clone(), system_program.clone()])?;
+    Ok(())
+}
+
+pub fn transfer_from_vault<'info>(
+    league_key: &Pubkey,
+    vault_bump: u8,
+    vault: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let signer_seeds: &[&[u8]] = &[b"prize_pool", league_key.as_ref(), &[vault_bump]];
+    let ix = system_instruction::transfer(&vault.key(), &to.key(), amount);
+    invoke_signed(&ix, &[vault.clone(), to.clone(), system_program.clone()], &[signer_seeds])?;
+    Ok(())
+}
+
+/// Token accounts needed to move funds in a token-denominated league.
+pub struct TokenTransferAccounts<'a, 'info> {
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_vault: &'a InterfaceAccount<'info, TokenAccount>,
+    pub user_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'a, 'info> TokenTransferAccounts<'a, 'info> {
+    /// Unwraps the optional token accounts of an instruction and checks them against the
+    /// league and the wallet that owns `user_token_account`.
+    pub fn for_league(
+        league: &League,
+        user: &Pubkey,
+        mint: &'a Option<InterfaceAccount<'info, Mint>>,
+        token_vault: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        user_token_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        token_program: &'a Option<Interface<'info, TokenInterface>>,
+    ) -> Result<Self> {
+        let (Some(mint), Some(token_vault), Some(user_token_account), Some(token_program)) =
+            (mint, token_vault, user_token_account, token_program)
+        else {
+            return err!(FflError::MissingTokenAccounts);
+        };
+
+        require!(league.mint == Some(mint.key()), FflError::InvalidMint);
+        require!(user_token_account.mint == mint.key(), FflError::InvalidMint);
+        require!(user_token_account.owner == *user, FflError::InvalidTokenAccount);
+        require!(*mint.to_account_info().owner == token_program.key(), FflError::InvalidMint);
+
+        Ok(Self { mint, token_vault, user_token_account, token_program })
+    }
+
+    pub fn deposit(&self, authority: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.user_token_account.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.token_vault.to_account_info(),
+                authority: authority.clone(),
+            },
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+
+    pub fn withdraw(&self, league: &Account<'info, League>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let league_id = league.league_id.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[b"league", league_id.as_ref(), &[league.bump]];
+        let signer = &[signer_seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.token_vault.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.user_token_account.to_account_info(),
+                authority: league.to_account_info(),
+            },
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+}
+
+pub fn init_token_vault(ctx: Context<InitTokenVault>) -> Result<()> {
+    let league = &mut ctx.accounts.league;
+
+    require!(league.state == LeagueState::Setup, FflError::NotSetup);
+    require!(league.players.is_empty(), FflError::CurrencyLocked);
+    require_supported_mint(&ctx.accounts.mint.to_account_info())?;
+
+    league.mint = Some(ctx.accounts.mint.key());
+    league.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(LeagueCurrencySet {
+        league_id: league.league_id,
+        mint: ctx.accounts.mint.key(),
+    });
+
+    Ok(())
+}
+
+/// Rejects Token-2022 mints whose transfers don't move exactly `amount` with the standard
+/// accounts. Buy-ins, refunds and payouts all assume that: a transfer fee would leave the
+/// vault short of what it owes, and a transfer hook needs extra accounts we never pass.
+fn require_supported_mint(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    require_supported_mint_data(&mint.try_borrow_data()?)
+}
+
+/// A permanent delegate could drain the vault, a non-transferable mint can't be paid out,
+/// and a default-frozen account state can leave player accounts unable to receive.
+fn require_supported_mint_data(data: &[u8]) -> Result<()> {
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(data)?;
+    let unsupported = mint_state
+        .get_extension_types()?
+        .into_iter()
+        .any(|extension| matches!(
+            extension,
+            ExtensionType::TransferFeeConfig
+                | ExtensionType::TransferHook
+                | ExtensionType::PermanentDelegate
+                | ExtensionType::NonTransferable
+                | ExtensionType::DefaultAccountState
+        ));
+    require!(!unsupported, FflError::UnsupportedMintExtension);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitTokenVault<'info> {
+    #[account(mut, has_one = creator @ FflError::NotCommissioner)]
+    pub league: Account<'info, League>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // Owned by the league PDA so only the program can move escrowed tokens
+    #[account(
+        init,
+        seeds = [b"token_vault", league.key().as_ref()],
+        bump,
+        payer = creator,
+        token::mint = mint,
+        token::authority = league,
+        token::token_program = token_program
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        default_account_state::DefaultAccountState, mint_close_authority::MintCloseAuthority,
+        non_transferable::NonTransferable, permanent_delegate::PermanentDelegate,
+        transfer_fee::TransferFeeConfig, transfer_hook::TransferHook, BaseStateWithExtensionsMut,
+        StateWithExtensionsMut,
+    };
+    use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+
+    fn mint_with(extension: ExtensionType) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<MintState>(&[extension]).unwrap();
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        match extension {
+            ExtensionType::TransferFeeConfig => { state.init_extension::<TransferFeeConfig>(true).unwrap(); }
+            ExtensionType::TransferHook => { state.init_extension::<TransferHook>(true).unwrap(); }
+            ExtensionType::PermanentDelegate => { state.init_extension::<PermanentDelegate>(true).unwrap(); }
+            ExtensionType::NonTransferable => { state.init_extension::<NonTransferable>(true).unwrap(); }
+            ExtensionType::DefaultAccountState => { state.init_extension::<DefaultAccountState>(true).unwrap(); }
+            ExtensionType::MintCloseAuthority => { state.init_extension::<MintCloseAuthority>(true).unwrap(); }
+            _ => unreachable!(),
+        }
+        state.base = MintState { decimals: 6, is_initialized: true, ..MintState::default() };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn rejects_extensions_that_break_escrow() {
+        for extension in [
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::TransferHook,
+            ExtensionType::PermanentDelegate,
+            ExtensionType::NonTransferable,
+            ExtensionType::DefaultAccountState,
+        ] {
+            assert!(require_supported_mint_data(&mint_with(extension)).is_err(), "{:?}", extension);
+        }
+    }
+
+    #[test]
+    fn accepts_harmless_extensions() {
+        assert!(require_supported_mint_data(&mint_with(ExtensionType::MintCloseAuthority)).is_ok());
+    }
+}
@@ -1,13 +1,16 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{system_instruction, program::{invoke, invoke_signed}};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 pub mod state;
 pub mod errors;
 pub mod instructions;
+pub mod events;
+pub mod xp;
 
 use state::*;
 use errors::*;
 use instructions::*;
+use events::*;
 
 declare_id!("HtJHB7t3esZkEdZhvUHQNYj4RYXrQsGxqzRoyMzmsBJQ");
 
@@ -18,54 +21,138 @@ pub mod anchor {
     pub fn create_league(
         ctx: Context<CreateLeague>,
         league_id: u64,
-        buy_in_amount: u64,
-        sessions_total: u8,
-        rounds_per_session: u8,
+        args: CreateLeagueArgs,
     ) -> Result<()> {
+        let CreateLeagueArgs {
+            buy_in_amount,
+            sessions_total,
+            rounds_per_session,
+            scoring_rules,
+            payout_structure,
+            commissioner_fee_bps,
+            setup_deadline,
+            mode,
+            min_players,
+            max_players,
+            pick_time_limit,
+        } = args;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!((1..=MAX_SESSIONS).contains(&sessions_total), FflError::InvalidSessionConfig);
+        require!(rounds_per_session > 0, FflError::InvalidSessionConfig);
+        require!(
+            MIN_PLAYERS <= min_players && min_players <= max_players && max_players <= MAX_PLAYERS,
+            FflError::InvalidPlayerLimits
+        );
+        // DraftPick::pick_index is a u8
+        require!(
+            rounds_per_session as u16 * max_players as u16 <= u8::MAX as u16 + 1,
+            FflError::InvalidSessionConfig
+        );
+        if mode == LeagueMode::HeadToHead {
+            require!(min_players == 2 && max_players == 2, FflError::InvalidPlayerLimits);
+            require!(payout_structure == PayoutStructure::WinnerTakeAll, FflError::InvalidPayoutStructure);
+        }
+        require!(commissioner_fee_bps <= MAX_COMMISSIONER_FEE_BPS, FflError::FeeTooHigh);
+        require!(setup_deadline == 0 || setup_deadline > now, FflError::InvalidDeadline);
+        require!((0..=MAX_PICK_TIME_LIMIT).contains(&pick_time_limit), FflError::InvalidPickTimeLimit);
+
+        let scoring = scoring_rules.unwrap_or_default();
+        scoring.validate()?;
+        payout_structure.validate()?;
+
         let league = &mut ctx.accounts.league;
         league.league_id = league_id;
         league.creator = ctx.accounts.creator.key();
         league.buy_in_amount = buy_in_amount;
+        league.protocol_fee_bps = ctx.accounts.config.protocol_fee_bps;
+        league.commissioner_fee_bps = commissioner_fee_bps;
+        league.setup_deadline = setup_deadline;
         league.prize_pool_vault = ctx.accounts.prize_pool_vault.key();
+        league.vault_bump = ctx.bumps.prize_pool_vault;
+        league.mint = None;
+        league.mode = mode;
+        league.min_players = min_players;
+        league.max_players = max_players;
         league.sessions_total = sessions_total;
         league.rounds_per_session = rounds_per_session;
         league.current_session = 1;
         league.state = LeagueState::Setup;
-        league.created_at = Clock::get()?.unix_timestamp;
-        league.updated_at = Clock::get()?.unix_timestamp;
+        league.created_at = now;
+        league.updated_at = now;
         league.session_picks_count = 0;
+        league.session_resolved_count = 0;
+        league.pick_time_limit = pick_time_limit;
+        league.pick_deadline = 0;
+        league.resolver = None;
+        league.co_commissioners = Vec::new();
+        league.draft_seed = [0; 32];
+        league.entropy_reveals = 0;
+        league.reveal_deadline = 0;
+        league.draft_slot = 0;
+        league.scoring = scoring;
+        league.payout_structure = payout_structure;
         league.bump = ctx.bumps.league;
         
         league.players = Vec::new();
         league.draft_order = Vec::new();
 
+        // Keep the vault rent-exempt so it can receive and pay out any amount
+        let vault = ctx.accounts.prize_pool_vault.to_account_info();
+        let shortfall = vault::vault_rent_reserve()?.saturating_sub(vault.lamports());
+        vault::transfer_to_vault(
+            &ctx.accounts.creator.to_account_info(),
+            &vault,
+            &ctx.accounts.system_program.to_account_info(),
+            shortfall,
+        )?;
+
+        emit!(LeagueCreated {
+            league_id,
+            league: league.key(),
+            creator: league.creator,
+            mode,
+            buy_in_amount,
+            sessions_total,
+            rounds_per_session,
+            min_players,
+            max_players,
+        });
+
         Ok(())
     }
 
-    pub fn join_league(ctx: Context<JoinLeague>) -> Result<()> {
+    pub fn join_league(ctx: Context<JoinLeague>, entropy_commitment: [u8; 32]) -> Result<()> {
         let league = &mut ctx.accounts.league;
         let player = &mut ctx.accounts.player;
         let player_state = &mut ctx.accounts.player_state;
 
         // Checks
         require!(league.state == LeagueState::Setup, FflError::NotSetup);
-        require!(league.players.len() < 12, FflError::LeagueFull); // Max 12 players
+        require!(!league.is_locked(), FflError::LeagueLocked);
+        require!(league.players.len() < league.max_players as usize, FflError::LeagueFull);
         require!(!league.players.contains(&player.key()), FflError::AlreadyJoined);
 
-        // Transfer buy-in (SOL) to treasury
-        let ix = system_instruction::transfer(
-            &player.key(),
-            &ctx.accounts.treasury.key(),
-            league.buy_in_amount,
-        );
-        invoke(
-            &ix,
-            &[
-                player.to_account_info(),
-                ctx.accounts.treasury.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-        )?;
+        // Escrow buy-in in the league's vault
+        match league.mint {
+            None => vault::transfer_to_vault(
+                &player.to_account_info(),
+                &ctx.accounts.prize_pool_vault.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                league.buy_in_amount,
+            )?,
+            Some(_) => {
+                let token_accounts = vault::TokenTransferAccounts::for_league(
+                    league,
+                    &player.key(),
+                    &ctx.accounts.mint,
+                    &ctx.accounts.token_vault,
+                    &ctx.accounts.player_token_account,
+                    &ctx.accounts.token_program,
+                )?;
+                token_accounts.deposit(&player.to_account_info(), league.buy_in_amount)?;
+            }
+        }
 
         // Initialize PlayerState
         player_state.league_id = league.league_id;
@@ -74,36 +161,97 @@ pub mod anchor {
         player_state.streak = 0;
         player_state.xp = 0;
         player_state.bonuses = 0;
+        player_state.bonus_totals = BonusTotals::default();
         player_state.has_claimed = false;
+        player_state.entropy_commitment = entropy_commitment;
+        player_state.entropy_revealed = false;
+        player_state.entropy_contribution = [0; 32];
         player_state.bump = ctx.bumps.player_state;
 
+        // First league for this wallet: start its profile
+        let profile = &mut ctx.accounts.profile;
+        if profile.wallet == Pubkey::default() {
+            profile.wallet = player.key();
+            profile.rating = INITIAL_RATING;
+            profile.bump = ctx.bumps.profile;
+        }
+
         // Add to league
         league.players.push(player.key());
 
+        // A full league locks itself and opens the reveal window
+        if league.players.len() == league.max_players as usize {
+            league.lock(Clock::get()?.unix_timestamp);
+            emit!(LeagueLocked {
+                league_id: league.league_id,
+                reveal_deadline: league.reveal_deadline,
+            });
+        }
+
+        emit!(PlayerJoined {
+            league_id: league.league_id,
+            player: player.key(),
+            player_count: league.players.len() as u8,
+        });
+
         Ok(())
     }
 
+    pub fn lock_league(ctx: Context<LockLeague>) -> Result<()> {
+        instructions::entropy::lock_league(ctx)
+    }
+
+    pub fn reveal_entropy(ctx: Context<RevealEntropy>, secret: [u8; 32]) -> Result<()> {
+        instructions::entropy::reveal_entropy(ctx, secret)
+    }
+
+    pub fn remove_unrevealed(ctx: Context<RemoveUnrevealed>) -> Result<()> {
+        instructions::entropy::remove_unrevealed(ctx)
+    }
+
+    pub fn cancel_league(ctx: Context<CancelLeague>) -> Result<()> {
+        instructions::refund::cancel_league(ctx)
+    }
+
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        instructions::refund::claim_refund(ctx)
+    }
+
+    pub fn leave_league(ctx: Context<LeaveLeague>) -> Result<()> {
+        instructions::refund::leave_league(ctx)
+    }
+
+    pub fn init_token_vault(ctx: Context<InitTokenVault>) -> Result<()> {
+        instructions::vault::init_token_vault(ctx)
+    }
+
+    pub fn reschedule_draft(ctx: Context<RescheduleDraft>) -> Result<()> {
+        instructions::entropy::reschedule_draft(ctx)
+    }
+
     pub fn start_draft(ctx: Context<StartDraft>) -> Result<()> {
         let league = &mut ctx.accounts.league;
         
         require!(league.state == LeagueState::Setup, FflError::NotSetup);
-        require!(league.players.len() >= 2, FflError::LeagueFull); // Min 2 players for testing, PRD says 8
+        require!(league.is_locked(), FflError::LeagueNotLocked);
+        require!(league.players.len() >= league.min_players as usize, FflError::NotEnoughPlayers);
+        require!(league.entropy_reveals as usize == league.players.len(), FflError::EntropyNotRevealed);
 
-        // Randomize draft order (simple pseudo-random using slot/timestamp)
-        // In production, use VRF or similar. For MVP/Hackathon, simple shuffle is ok.
-        let mut draft_order = league.players.clone();
-        let clock = Clock::get()?;
-        let seed = clock.unix_timestamp as u64 + clock.slot;
-        
-        // Simple Fisher-Yates shuffle
-        for i in (1..draft_order.len()).rev() {
-            let j = (seed % (i as u64 + 1)) as usize;
-            draft_order.swap(i, j);
-        }
-        
-        league.draft_order = draft_order;
+        // Draft order comes from the combined entropy of every player, plus the hash of a slot
+        // fixed after the last reveal, so neither revealing nor the start time can steer it
+        let slot_hash = instructions::entropy::draft_slot_hash(league, &ctx.accounts.slot_hashes)?;
+        league.draft_seed = instructions::entropy::mix_slot_hash(&league.draft_seed, &slot_hash);
+        league.draft_order = instructions::entropy::shuffle_draft_order(&league.players, &league.draft_seed);
         league.state = LeagueState::Drafting;
         league.session_picks_count = 0;
+        league.session_resolved_count = 0;
+        league.start_pick_clock(Clock::get()?.unix_timestamp);
+
+        emit!(DraftStarted {
+            league_id: league.league_id,
+            session: league.current_session,
+            draft_order: league.draft_order.clone(),
+        });
         
         Ok(())
     }
@@ -116,68 +264,108 @@ pub mod anchor {
     ) -> Result<()> {
         let league = &mut ctx.accounts.league;
         let draft_pick = &mut ctx.accounts.draft_pick;
+        let player_state = &mut ctx.accounts.player_state;
         let player = &ctx.accounts.player;
 
         require!(league.state == LeagueState::Drafting, FflError::NotDrafting);
-        
-        // Calculate turn
-        let total_picks = (league.rounds_per_session as usize) * league.players.len();
-        require!((league.session_picks_count as usize) < total_picks, FflError::SessionNotActive);
+        require!(snapshot_odds <= 10000, FflError::InvalidProbability);
 
-        let pick_index = league.session_picks_count as usize;
-        let round = pick_index / league.players.len();
-        let position_in_round = pick_index % league.players.len();
-        
-        // Snake draft logic
-        let player_index = if round % 2 == 0 {
-            position_in_round
-        } else {
-            league.players.len() - 1 - position_in_round
-        };
-        
-        let expected_player = league.draft_order[player_index];
+        // A late pick still counts as long as nobody has skipped the turn yet
+        let expected_player = league.current_drafter().ok_or(FflError::SessionNotActive)?;
         require!(player.key() == expected_player, FflError::NotYourTurn);
 
-        // Initialize DraftPick
-        draft_pick.league_id = league.league_id;
-        draft_pick.player = player.key();
-        draft_pick.session_index = league.current_session;
-        draft_pick.pick_index = league.session_picks_count as u8;
-        draft_pick.market_id = market_id;
-        draft_pick.prediction = prediction;
-        draft_pick.snapshot_odds = snapshot_odds;
-        draft_pick.resolved = false;
-        draft_pick.final_points = 0;
-        draft_pick.bump = ctx.bumps.draft_pick;
-
-        // Update league
-        league.session_picks_count += 1;
-        
-        // Check if session draft is complete
-        if league.session_picks_count as usize == total_picks {
-            league.state = LeagueState::Active;
-        }
+        draft_pick.set_inner(DraftPick::new(
+            league,
+            player.key(),
+            market_id,
+            prediction,
+            snapshot_odds,
+            ctx.bumps.draft_pick,
+        ));
+        instructions::draft::record_pick(league, &draft_pick.key(), draft_pick, player_state)
+    }
 
-        Ok(())
+    pub fn skip_turn(ctx: Context<SkipTurn>) -> Result<()> {
+        instructions::draft::skip_turn(ctx)
+    }
+
+    pub fn set_draft_queue(ctx: Context<SetDraftQueue>, entries: Vec<QueuedPick>) -> Result<()> {
+        instructions::draft::set_draft_queue(ctx, entries)
+    }
+
+    pub fn close_draft_queue(ctx: Context<CloseDraftQueue>) -> Result<()> {
+        instructions::draft::close_draft_queue(ctx)
+    }
+
+    pub fn auto_pick<'info>(ctx: Context<'_, '_, 'info, 'info, AutoPick<'info>>) -> Result<()> {
+        instructions::draft::auto_pick(ctx)
+    }
+
+    pub fn register_market(
+        ctx: Context<RegisterMarket>,
+        market_id: String,
+        close_time: i64,
+    ) -> Result<()> {
+        instructions::market::register_market(ctx, market_id, close_time)
     }
 
-    pub fn resolve_market(
-        ctx: Context<ResolveMarket>,
+    pub fn settle_market(
+        ctx: Context<SettleMarket>,
         market_id: String,
-        outcome: bool,
+        outcome: MarketOutcome,
         final_prob: u32,
     ) -> Result<()> {
-        instructions::resolve::resolve_market(ctx, market_id, outcome, final_prob)
+        instructions::market::settle_market(ctx, market_id, outcome, final_prob)
+    }
+
+    pub fn resolve_market(ctx: Context<ResolveMarket>, market_id: String) -> Result<()> {
+        instructions::resolve::resolve_market(ctx, market_id)
+    }
+
+    pub fn batch_resolve_market<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchResolveMarket<'info>>,
+        market_id: String,
+    ) -> Result<()> {
+        instructions::resolve::batch_resolve_market(ctx, market_id)
+    }
+
+    pub fn add_co_commissioner(ctx: Context<ManageCoCommissioners>, co_commissioner: Pubkey) -> Result<()> {
+        instructions::commissioner::add_co_commissioner(ctx, co_commissioner)
+    }
+
+    pub fn remove_co_commissioner(ctx: Context<ManageCoCommissioners>, co_commissioner: Pubkey) -> Result<()> {
+        instructions::commissioner::remove_co_commissioner(ctx, co_commissioner)
+    }
+
+    pub fn advance_session<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AdvanceSession<'info>>,
+        rotate_draft_order: bool,
+    ) -> Result<()> {
+        instructions::session::advance_session(ctx, rotate_draft_order)
     }
 
-    pub fn end_season(ctx: Context<EndSeason>) -> Result<()> {
+    pub fn end_season<'info>(ctx: Context<'_, '_, 'info, 'info, EndSeason<'info>>) -> Result<()> {
         instructions::payout::end_season(ctx)
     }
 
+    pub fn finalize_standings<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeStandings<'info>>,
+    ) -> Result<()> {
+        instructions::payout::finalize_standings(ctx)
+    }
+
+    pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+        instructions::payout::claim_fees(ctx)
+    }
+
     pub fn claim_payout(ctx: Context<ClaimPayout>) -> Result<()> {
         instructions::payout::claim_payout(ctx)
     }
-}
+
+    pub fn update_ratings<'info>(ctx: Context<'_, '_, 'info, 'info, UpdateRatings<'info>>) -> Result<()> {
+        instructions::profile::update_ratings(ctx)
+    }
+
     pub fn propose_trade(ctx: Context<ProposeTrade>, trade_id: u64) -> Result<()> {
         instructions::trades::propose_trade(ctx, trade_id)
     }
@@ -185,26 +373,49 @@ pub mod anchor {
     pub fn respond_to_trade(ctx: Context<RespondToTrade>, accept: bool) -> Result<()> {
         instructions::trades::respond_to_trade(ctx, accept)
     }
+
+    pub fn initialize_config(ctx: Context<InitializeConfig>, resolver: Pubkey) -> Result<()> {
+        instructions::config::initialize_config(ctx, resolver)
+    }
+
+    pub fn set_fee_config(
+        ctx: Context<UpdateConfig>,
+        protocol_fee_bps: u16,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::config::set_fee_config(ctx, protocol_fee_bps, fee_recipient)
+    }
+
+    pub fn set_resolver(ctx: Context<UpdateConfig>, new_resolver: Pubkey) -> Result<()> {
+        instructions::config::set_resolver(ctx, new_resolver)
+    }
+
+    pub fn set_league_resolver(ctx: Context<SetLeagueResolver>, resolver: Option<Pubkey>) -> Result<()> {
+        instructions::config::set_league_resolver(ctx, resolver)
+    }
 }
 
 #[derive(Accounts)]
-#[instruction(league_id: u64)]
+#[instruction(league_id: u64, args: CreateLeagueArgs)]
 pub struct CreateLeague<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     #[account(
         init,
         seeds = [b"league", league_id.to_le_bytes().as_ref()],
         bump,
         payer = creator,
-        space = League::LEN
+        space = League::space(args.max_players as usize)
     )]
     pub league: Account<'info, League>,
     
-    /// CHECK: PDA for prize pool vault
     #[account(
+        mut,
         seeds = [b"prize_pool", league.key().as_ref()],
         bump
     )]
-    pub prize_pool_vault: AccountInfo<'info>,
+    pub prize_pool_vault: SystemAccount<'info>,
     
     #[account(mut)]
     pub creator: Signer<'info>,
@@ -224,10 +435,30 @@ pub struct JoinLeague<'info> {
         space = PlayerState::LEN
     )]
     pub player_state: Account<'info, PlayerState>,
+
+    #[account(
+        init_if_needed,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+        payer = player,
+        space = Profile::LEN
+    )]
+    pub profile: Account<'info, Profile>,
     
-    /// CHECK: Treasury wallet to receive buy-ins
+    #[account(
+        mut,
+        seeds = [b"prize_pool", league.key().as_ref()],
+        bump = league.vault_bump
+    )]
+    pub prize_pool_vault: SystemAccount<'info>,
+
+    // Token leagues only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut, seeds = [b"token_vault", league.key().as_ref()], bump)]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut)]
-    pub treasury: AccountInfo<'info>,
+    pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     
     #[account(mut)]
     pub player: Signer<'info>,
@@ -236,10 +467,13 @@ pub struct JoinLeague<'info> {
 
 #[derive(Accounts)]
 pub struct StartDraft<'info> {
+    // Permissionless: the order is fixed by the draft slot, so anyone may start the draft
     #[account(mut)]
     pub league: Account<'info, League>,
-    #[account(mut)]
-    pub creator: Signer<'info>,
+
+    /// CHECK: The SlotHashes sysvar, read raw because it is too large to deserialize
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -262,6 +496,13 @@ pub struct MakePick<'info> {
         space = DraftPick::LEN
     )]
     pub draft_pick: Account<'info, DraftPick>,
+
+    #[account(
+        mut,
+        seeds = [b"player_state", league.key().as_ref(), player.key().as_ref()],
+        bump = player_state.bump
+    )]
+    pub player_state: Account<'info, PlayerState>,
     
     #[account(mut)]
     pub player: Signer<'info>,
@@ -1,7 +1,45 @@
 use anchor_lang::prelude::*;
+use crate::errors::FflError;
 
 pub mod trade;
+pub mod config;
+pub mod market;
+pub mod standings;
+pub mod profile;
+pub mod draft_queue;
 pub use trade::*;
+pub use config::*;
+pub use market::*;
+pub use standings::*;
+pub use profile::*;
+pub use draft_queue::*;
+
+// Session indices start at 1 and index into PlayerState::session_stats.
+pub const MAX_SESSIONS: u8 = 15;
+
+// Hard cap on league size; each league picks its own limits within it.
+// Ending a season passes every PlayerState in one transaction, which bounds this.
+pub const MIN_PLAYERS: u8 = 2;
+pub const MAX_PLAYERS: u8 = 16;
+
+pub const MAX_CO_COMMISSIONERS: usize = 3;
+pub const MAX_PAYOUT_PLACES: usize = MAX_PLAYERS as usize;
+
+// Fee caps, in basis points of the prize pool
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+pub const MAX_COMMISSIONER_FEE_BPS: u16 = 1_000;
+
+// Longest pick clock a league may set, in seconds
+pub const MAX_PICK_TIME_LIMIT: i64 = 7 * 24 * 60 * 60;
+
+// How long players have to reveal their draft entropy once a league is locked
+pub const ENTROPY_REVEAL_WINDOW: i64 = 24 * 60 * 60;
+
+// Slots between the last reveal and the slot whose hash seeds the draft order
+pub const DRAFT_SLOT_DELAY: u64 = 32;
+
+// Polymarket IDs are also used as PDA seeds, so they must fit in a single seed.
+pub const MAX_MARKET_ID_LEN: usize = 32;
 
 
 #[account]
@@ -9,7 +47,15 @@ pub struct League {
     pub league_id: u64,
     pub creator: Pubkey,
     pub buy_in_amount: u64,
+    pub protocol_fee_bps: u16, // Snapshot of Config::protocol_fee_bps at creation
+    pub commissioner_fee_bps: u16,
+    pub setup_deadline: i64, // After this, anyone may cancel a league still in Setup; 0 = never
     pub prize_pool_vault: Pubkey,
+    pub vault_bump: u8,
+    pub mint: Option<Pubkey>, // SPL mint for buy-ins and payouts; None means SOL
+    pub mode: LeagueMode,
+    pub min_players: u8,
+    pub max_players: u8,
     pub players: Vec<Pubkey>,
     pub draft_order: Vec<Pubkey>,
     pub sessions_total: u8,
@@ -19,18 +65,39 @@ pub struct League {
     pub created_at: i64,
     pub updated_at: i64,
     pub session_picks_count: u16,
+    pub session_resolved_count: u16, // Picks of the current session that have been resolved, plus forfeited slots
+    pub pick_time_limit: i64, // Seconds each drafter has before their turn can be skipped; 0 = no clock
+    pub pick_deadline: i64, // When the current turn can be skipped; 0 when there is no clock running
     pub total_points: i64,
+    pub resolver: Option<Pubkey>, // Overrides Config::resolver for this league
+    pub co_commissioners: Vec<Pubkey>, // Delegates with commissioner rights, except managing this list
+    pub draft_seed: [u8; 32], // XOR of every revealed player contribution
+    pub entropy_reveals: u8,
+    pub reveal_deadline: i64, // Set when the league is locked; 0 while it is still open to joins
+    pub draft_slot: u64, // Slot whose hash seeds the draft order; 0 until every remaining player has revealed
+    pub scoring: ScoringRules,
+    pub payout_structure: PayoutStructure,
     pub bump: u8,
 }
 
 impl League {
-    pub const LEN: usize = 8 + // discriminator
+    /// Account size for a league that holds up to `max_players` players.
+    pub fn space(max_players: usize) -> usize {
+        8 + // discriminator
         8 + // league_id
         32 + // creator
         8 + // buy_in_amount
+        2 + // protocol_fee_bps
+        2 + // commissioner_fee_bps
+        8 + // setup_deadline
         32 + // prize_pool_vault
-        4 + (32 * 12) + // players (max 12)
-        4 + (32 * 12) + // draft_order (max 12)
+        1 + // vault_bump
+        1 + 32 + // mint
+        1 + // mode
+        1 + // min_players
+        1 + // max_players
+        4 + (32 * max_players) + // players
+        4 + (32 * max_players) + // draft_order
         1 + // sessions_total
         1 + // rounds_per_session
         1 + // current_session
@@ -38,8 +105,241 @@ impl League {
         8 + // created_at
         8 + // updated_at
         2 + // session_picks_count
+        2 + // session_resolved_count
+        8 + // pick_time_limit
+        8 + // pick_deadline
         8 + // total_points
-        1; // bump
+        1 + 32 + // resolver
+        4 + (32 * MAX_CO_COMMISSIONERS) + // co_commissioners
+        32 + // draft_seed
+        1 + // entropy_reveals
+        8 + // reveal_deadline
+        8 + // draft_slot
+        ScoringRules::LEN + // scoring
+        PayoutStructure::LEN + // payout_structure
+        1 // bump
+    }
+
+    /// The authority allowed to resolve picks in this league.
+    pub fn resolver_authority(&self, config: &Config) -> Pubkey {
+        self.resolver.unwrap_or(config.resolver)
+    }
+
+    /// Whether `key` is the creator or one of the co-commissioners.
+    pub fn is_commissioner(&self, key: &Pubkey) -> bool {
+        *key == self.creator || self.co_commissioners.contains(key)
+    }
+
+    /// Whether `key` may drive the season forward (a commissioner or the resolver authority).
+    pub fn is_season_authority(&self, key: &Pubkey, config: &Config) -> bool {
+        self.is_commissioner(key) || *key == self.resolver_authority(config)
+    }
+
+    /// A locked league takes no more joins or leaves and is in its entropy reveal phase.
+    pub fn is_locked(&self) -> bool {
+        self.reveal_deadline != 0
+    }
+
+    /// Closes joins and opens the entropy reveal window.
+    pub fn lock(&mut self, now: i64) {
+        self.reveal_deadline = now + ENTROPY_REVEAL_WINDOW;
+        self.updated_at = now;
+    }
+
+    /// Once every remaining player has revealed, fixes the future slot whose hash
+    /// `start_draft` mixes into the seed.
+    pub fn schedule_draft_slot(&mut self, current_slot: u64) {
+        if !self.players.is_empty() && self.entropy_reveals as usize == self.players.len() {
+            self.draft_slot = current_slot + DRAFT_SLOT_DELAY;
+        }
+    }
+
+    /// The player whose turn it is in the snake draft, or None outside of a draft.
+    pub fn current_drafter(&self) -> Option<Pubkey> {
+        let drafters = self.draft_order.len();
+        let pick_index = self.session_picks_count as usize;
+        if self.state != LeagueState::Drafting || drafters == 0 || pick_index >= self.session_slots() {
+            return None;
+        }
+
+        let round = pick_index / drafters;
+        let position_in_round = pick_index % drafters;
+        let drafter_index = if round % 2 == 0 {
+            position_in_round
+        } else {
+            drafters - 1 - position_in_round
+        };
+        Some(self.draft_order[drafter_index])
+    }
+
+    /// Number of draft slots in a session.
+    pub fn session_slots(&self) -> usize {
+        self.rounds_per_session as usize * self.draft_order.len()
+    }
+
+    /// Starts the pick clock for whoever drafts next, if the league has one.
+    pub fn start_pick_clock(&mut self, now: i64) {
+        self.pick_deadline = if self.pick_time_limit > 0 { now + self.pick_time_limit } else { 0 };
+    }
+
+    /// Moves the draft past the current slot, which was either picked or forfeited, and
+    /// activates the session once every slot is used.
+    pub fn advance_draft_turn(&mut self, now: i64) {
+        self.session_picks_count += 1;
+        if self.session_picks_count as usize == self.session_slots() {
+            self.state = LeagueState::Active;
+            self.pick_deadline = 0;
+        } else {
+            self.start_pick_clock(now);
+        }
+        self.updated_at = now;
+    }
+
+    /// True once the current session's draft is done and every pick in it is resolved.
+    pub fn is_session_complete(&self) -> bool {
+        self.state == LeagueState::Active && self.session_resolved_count == self.session_picks_count
+    }
+}
+
+/// League settings chosen by the commissioner at `create_league`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateLeagueArgs {
+    pub buy_in_amount: u64,
+    pub sessions_total: u8,
+    pub rounds_per_session: u8,
+    pub scoring_rules: Option<ScoringRules>, // None selects the standard profile
+    pub payout_structure: PayoutStructure,
+    pub commissioner_fee_bps: u16,
+    pub setup_deadline: i64, // Unix timestamp, or 0 to only allow the commissioners to cancel
+    pub mode: LeagueMode,
+    pub min_players: u8,
+    pub max_players: u8,
+    pub pick_time_limit: i64, // Seconds per pick, or 0 for an untimed draft
+}
+
+/// Scoring parameters chosen by the commissioner at `create_league`.
+/// Probabilities and multipliers are basis points (10000 = 1.0).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ScoringRules {
+    pub favorite_threshold_bps: u16, // p_pred at or above this is a favorite
+    pub balanced_threshold_bps: u16, // p_pred at or above this (and below favorite) is balanced
+    pub favorite_multiplier_bps: u16,
+    pub balanced_multiplier_bps: u16,
+    pub longshot_multiplier_bps: u16,
+    pub loss_factor: u16, // Points lost on a wrong pick are -loss_factor * p_pred
+    pub longshot_bonus_threshold_bps: u16,
+    pub longshot_bonus: u16,
+    pub streak_interval: u16, // Streak bonus is paid every `streak_interval` consecutive wins
+    pub streak_bonus: u16,
+    pub clean_sweep_bonus: u16,
+}
+
+impl ScoringRules {
+    pub const LEN: usize = 11 * 2;
+
+    pub const MIN_MULTIPLIER_BPS: u16 = 5_000;
+    pub const MAX_MULTIPLIER_BPS: u16 = 50_000;
+    pub const MAX_LOSS_FACTOR: u16 = 100;
+    pub const MAX_BONUS: u16 = 500;
+    pub const MAX_STREAK_INTERVAL: u16 = 100;
+
+    pub fn validate(&self) -> Result<()> {
+        let multipliers = [
+            self.favorite_multiplier_bps,
+            self.balanced_multiplier_bps,
+            self.longshot_multiplier_bps,
+        ];
+        let bonuses = [self.longshot_bonus, self.streak_bonus, self.clean_sweep_bonus];
+
+        require!(
+            self.balanced_threshold_bps > 0 &&
+            self.balanced_threshold_bps < self.favorite_threshold_bps &&
+            self.favorite_threshold_bps <= 10_000,
+            FflError::InvalidScoringRules
+        );
+        require!(
+            self.longshot_bonus_threshold_bps <= self.balanced_threshold_bps,
+            FflError::InvalidScoringRules
+        );
+        require!(
+            multipliers.iter().all(|m| (Self::MIN_MULTIPLIER_BPS..=Self::MAX_MULTIPLIER_BPS).contains(m)),
+            FflError::InvalidScoringRules
+        );
+        require!(self.loss_factor <= Self::MAX_LOSS_FACTOR, FflError::InvalidScoringRules);
+        require!(bonuses.iter().all(|b| *b <= Self::MAX_BONUS), FflError::InvalidScoringRules);
+        require!(
+            (1..=Self::MAX_STREAK_INTERVAL).contains(&self.streak_interval),
+            FflError::InvalidScoringRules
+        );
+        Ok(())
+    }
+}
+
+impl Default for ScoringRules {
+    /// The standard PRD scoring profile.
+    fn default() -> Self {
+        Self {
+            favorite_threshold_bps: 7_000,
+            balanced_threshold_bps: 4_000,
+            favorite_multiplier_bps: 10_000,
+            balanced_multiplier_bps: 12_000,
+            longshot_multiplier_bps: 15_000,
+            loss_factor: 30,
+            longshot_bonus_threshold_bps: 2_000,
+            longshot_bonus: 10,
+            streak_interval: 5,
+            streak_bonus: 25,
+            clean_sweep_bonus: 50,
+        }
+    }
+}
+
+/// How the prize pool is split once the season's standings are final.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum PayoutStructure {
+    WinnerTakeAll,
+    TopThree, // 60/30/10
+    Proportional, // By share of positive points
+    Custom(Vec<u16>), // Basis points per place, best first; must sum to 10000
+}
+
+impl PayoutStructure {
+    pub const LEN: usize = 1 + 4 + (2 * MAX_PAYOUT_PLACES);
+
+    pub fn validate(&self) -> Result<()> {
+        if let PayoutStructure::Custom(shares) = self {
+            require!(
+                !shares.is_empty() && shares.len() <= MAX_PAYOUT_PLACES,
+                FflError::InvalidPayoutStructure
+            );
+            require!(
+                shares.iter().map(|s| *s as u32).sum::<u32>() == 10_000,
+                FflError::InvalidPayoutStructure
+            );
+            // A lower place can never be paid more than a higher one
+            require!(
+                shares.windows(2).all(|pair| pair[0] >= pair[1]),
+                FflError::InvalidPayoutStructure
+            );
+        }
+        Ok(())
+    }
+
+    /// Basis points per place, or None when the split is proportional to points.
+    pub fn place_shares(&self) -> Option<Vec<u16>> {
+        match self {
+            PayoutStructure::WinnerTakeAll => Some(vec![10_000]),
+            PayoutStructure::TopThree => Some(vec![6_000, 3_000, 1_000]),
+            PayoutStructure::Proportional => None,
+            PayoutStructure::Custom(shares) => Some(shares.clone()),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LeagueMode {
+    Standard,
+    HeadToHead, // Exactly two players, winner takes all, a tie refunds both buy-ins
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -48,6 +348,7 @@ pub enum LeagueState {
     Drafting,
     Active,
     Completed,
+    Cancelled, // Players reclaim their buy-ins with claim_refund
 }
 
 #[account]
@@ -56,9 +357,15 @@ pub struct PlayerState {
     pub player: Pubkey,
     pub points: i64,
     pub streak: u16,
-    pub xp: u32,
-    pub bonuses: u32,
+    pub xp: u32, // Earned in this league; see crate::xp
+    pub bonuses: u32, // Sum of bonus_totals
+    pub bonus_totals: BonusTotals,
     pub has_claimed: bool,
+    pub entropy_commitment: [u8; 32], // hash(secret || player), revealed before the draft
+    pub entropy_revealed: bool,
+    pub entropy_contribution: [u8; 32], // What this player XORed into League::draft_seed
+    pub final_rank: u8, // 1-based, set when the season ends; tied players share a rank
+    pub payout_bps: u16, // Share of the prize pool, set when the season ends
     pub session_stats: [SessionStat; 16], // Max 16 sessions
     pub bump: u8,
 }
@@ -71,15 +378,44 @@ impl PlayerState {
         2 + // streak
         4 + // xp
         4 + // bonuses
+        BonusTotals::LEN + // bonus_totals
         1 + // has_claimed
-        (16 * 2) + // session_stats
+        32 + // entropy_commitment
+        1 + // entropy_revealed
+        32 + // entropy_contribution
+        1 + // final_rank
+        2 + // payout_bps
+        (16 * 3) + // session_stats
         1; // bump
+
+    pub fn level(&self) -> u32 {
+        crate::xp::level_for_xp(self.xp as u64)
+    }
+}
+
+/// Bonus points a player has earned over the season, per bonus type.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BonusTotals {
+    pub longshot_bonus: u32,
+    pub streak_bonus: u32,
+    pub clean_sweep_bonus: u32,
+}
+
+impl BonusTotals {
+    pub const LEN: usize = 4 + 4 + 4;
+
+    pub fn add(&mut self, breakdown: &PointsBreakdown) {
+        self.longshot_bonus += breakdown.longshot_bonus as u32;
+        self.streak_bonus += breakdown.streak_bonus as u32;
+        self.clean_sweep_bonus += breakdown.clean_sweep_bonus as u32;
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
 pub struct SessionStat {
     pub wins: u8,
     pub losses: u8,
+    pub voids: u8,
 }
 
 #[account]
@@ -92,11 +428,41 @@ pub struct DraftPick {
     pub prediction: Prediction,
     pub snapshot_odds: u32, // Scaled by 10000 (basis points)
     pub resolved: bool,
-    pub final_points: i32,
+    pub outcome: PickOutcome,
+    pub final_points: i32, // breakdown.total()
+    pub breakdown: PointsBreakdown,
+    pub traded: bool, // Changed owner through an accepted trade
     pub bump: u8,
 }
 
 impl DraftPick {
+    /// A fresh pick for the current slot of `league`'s draft.
+    pub fn new(
+        league: &League,
+        player: Pubkey,
+        market_id: String,
+        prediction: Prediction,
+        snapshot_odds: u32,
+        bump: u8,
+    ) -> Self {
+        Self {
+            league_id: league.league_id,
+            player,
+            session_index: league.current_session,
+            pick_index: league.session_picks_count as u8,
+            market_id,
+            prediction,
+            snapshot_odds,
+            resolved: false,
+            outcome: PickOutcome::Pending,
+            final_points: 0,
+            breakdown: PointsBreakdown::default(),
+            traded: false,
+            bump,
+        }
+    }
+
+
     // market_id is String, so size is variable. We need to set a max length.
     // Let's assume max 32 chars for market ID if it's a hash, or allocate more.
     // Polymarket IDs are often long strings or integers.
@@ -110,12 +476,47 @@ impl DraftPick {
         1 + // prediction
         4 + // snapshot_odds
         1 + // resolved
+        1 + // outcome
         4 + // final_points
+        PointsBreakdown::LEN + // breakdown
+        1 + // traded
         1; // bump
 }
 
+/// How a resolved pick's points were made up. All zero until the pick resolves, and for voids.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PointsBreakdown {
+    // Already includes multiplier_bps: 100 * (1 - p_pred) * multiplier for a win, or the
+    // (negative) loss points. Don't apply the multiplier again when explaining a score.
+    pub weighted_points: i32,
+    pub multiplier_bps: u16, // Difficulty multiplier that went into a win's weighted_points; 0 for losses
+    pub longshot_bonus: u16,
+    pub streak_bonus: u16,
+    pub clean_sweep_bonus: u16,
+}
+
+impl PointsBreakdown {
+    pub const LEN: usize = 4 + 2 + 2 + 2 + 2;
+
+    pub fn bonus_points(&self) -> u32 {
+        self.longshot_bonus as u32 + self.streak_bonus as u32 + self.clean_sweep_bonus as u32
+    }
+
+    pub fn total(&self) -> i64 {
+        self.weighted_points as i64 + self.bonus_points() as i64
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum Prediction {
     Yes,
     No,
 }
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PickOutcome {
+    Pending,
+    Won,
+    Lost,
+    Voided, // Market resolved INVALID; scores zero and does not affect streaks
+}
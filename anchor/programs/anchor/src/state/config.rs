@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+/// Program-wide settings, stored in a single PDA seeded by `b"config"`.
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub resolver: Pubkey, // Default authority allowed to resolve picks
+    pub protocol_fee_bps: u16, // Taken from each prize pool at finalization
+    pub fee_recipient: Pubkey,
+    pub bump: u8,
+}
+
+impl Config {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // admin
+        32 + // resolver
+        2 + // protocol_fee_bps
+        32 + // fee_recipient
+        1; // bump
+}
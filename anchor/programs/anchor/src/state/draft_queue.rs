@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::{Prediction, MAX_MARKET_ID_LEN};
+
+pub const MAX_QUEUE_LEN: usize = 10;
+
+/// A player's ranked wishlist for the draft, seeded by `[b"draft_queue", league, player]`.
+/// `auto_pick` drafts from it when the player's pick clock runs out.
+#[account]
+pub struct DraftQueue {
+    pub league: Pubkey,
+    pub player: Pubkey,
+    pub entries: Vec<QueuedPick>, // Highest priority first
+    pub bump: u8,
+}
+
+impl DraftQueue {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // league
+        32 + // player
+        4 + (QueuedPick::LEN * MAX_QUEUE_LEN) + // entries
+        1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct QueuedPick {
+    pub market_id: String,
+    pub prediction: Prediction,
+    pub snapshot_odds: u32, // Odds the player commits to, as for make_pick
+}
+
+impl QueuedPick {
+    pub const LEN: usize = 4 + MAX_MARKET_ID_LEN + 1 + 4;
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::MAX_MARKET_ID_LEN;
+use crate::errors::FflError;
+
+/// Canonical record of a Polymarket market, shared by every league that drafts it.
+#[account]
+pub struct Market {
+    pub market_id: String,
+    pub status: MarketStatus,
+    pub close_time: i64,
+    pub outcome: Option<MarketOutcome>, // Set once when the market is settled
+    pub final_prob: u32, // YES probability at resolution, scaled by 10000
+    pub resolved_at: i64,
+    pub bump: u8,
+}
+
+impl Market {
+    pub const LEN: usize = 8 + // discriminator
+        4 + MAX_MARKET_ID_LEN + // market_id
+        1 + // status
+        8 + // close_time
+        1 + 1 + // outcome
+        4 + // final_prob
+        8 + // resolved_at
+        1; // bump
+
+    /// The final outcome, or an error if the market has not been settled yet.
+    pub fn settled_outcome(&self) -> Result<MarketOutcome> {
+        match (self.status, self.outcome) {
+            (MarketStatus::Resolved, Some(outcome)) => Ok(outcome),
+            _ => err!(FflError::MarketNotResolved),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MarketStatus {
+    Open,
+    Resolved,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MarketOutcome {
+    Yes,
+    No,
+    Invalid,
+}
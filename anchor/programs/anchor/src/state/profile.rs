@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+pub const INITIAL_RATING: u16 = 1200;
+
+/// Global per-wallet record, seeded by `[b"profile", wallet]`. Created on the wallet's
+/// first `join_league` and updated by `update_ratings` when a league completes.
+#[account]
+pub struct Profile {
+    pub wallet: Pubkey,
+    pub rating: u16,
+    pub wins: u32, // Leagues finished alone in first place
+    pub losses: u32,
+    pub draws: u32, // Leagues finished tied for first place
+    pub leagues_played: u32,
+    pub xp: u64, // Lifetime XP across every league
+    pub bump: u8,
+}
+
+impl Profile {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // wallet
+        2 + // rating
+        4 + // wins
+        4 + // losses
+        4 + // draws
+        4 + // leagues_played
+        8 + // xp
+        1; // bump
+
+    pub fn level(&self) -> u32 {
+        crate::xp::level_for_xp(self.xp)
+    }
+}
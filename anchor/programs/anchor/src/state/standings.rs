@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+/// Frozen payout table for a completed league, created by `finalize_standings`.
+/// Amounts are computed once from the pool at finalization, so claims are order-independent.
+#[account]
+pub struct Standings {
+    pub league: Pubkey,
+    pub total_pool: u64, // Pool available to players when finalized, after fees
+    pub protocol_fee: u64,
+    pub commissioner_fee: u64,
+    pub fee_recipient: Pubkey, // Config::fee_recipient when finalized; receives protocol_fee
+    pub protocol_fee_claimed: bool,
+    pub commissioner_fee_claimed: bool,
+    pub entries: Vec<StandingEntry>, // Ordered by rank
+    pub finalized_at: i64,
+    pub ratings_updated: bool, // Set once update_ratings has applied this league to player profiles
+    pub bump: u8,
+}
+
+impl Standings {
+    pub fn space(players: usize) -> usize {
+        8 + // discriminator
+        32 + // league
+        8 + // total_pool
+        8 + // protocol_fee
+        8 + // commissioner_fee
+        32 + // fee_recipient
+        1 + // protocol_fee_claimed
+        1 + // commissioner_fee_claimed
+        4 + (StandingEntry::LEN * players) + // entries
+        8 + // finalized_at
+        1 + // ratings_updated
+        1 // bump
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct StandingEntry {
+    pub player: Pubkey,
+    pub rank: u8,
+    pub points: i64,
+    pub payout_bps: u16,
+    pub amount: u64,
+    pub claimed: bool,
+    pub xp: u32, // XP earned in the league, carried to the player's Profile by update_ratings
+}
+
+impl StandingEntry {
+    pub const LEN: usize = 32 + 1 + 8 + 2 + 8 + 1 + 4;
+}
@@ -1,5 +1,4 @@
 use anchor_lang::prelude::*;
-use crate::state::Prediction;
 
 #[account]
 pub struct TradeProposal {
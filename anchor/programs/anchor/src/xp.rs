@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::PlayerState;
+use crate::events::XpAwarded;
+
+// XP is awarded for taking part, not only for winning, so it only ever goes up.
+// Levels are derived from total XP and never stored; clients compute them with the same
+// formula (see `xp_for_level`), or read them from the XpAwarded and ProfileUpdated events.
+
+pub const XP_PICK_MADE: u32 = 10;
+pub const XP_CORRECT_CALL: u32 = 25;
+pub const XP_LONGSHOT_HIT: u32 = 50; // On top of XP_CORRECT_CALL
+// A long shot is a call made at under 20%. Fixed here rather than read from the league's
+// scoring rules, so XP means the same thing in every league.
+pub const XP_LONGSHOT_MAX_PROB_BPS: u32 = 2_000;
+pub const XP_TRADE_WON: u32 = 30; // A pick received in a trade that then resolves correct
+pub const XP_SEASON_FINISHED: u32 = 100;
+
+// Reaching level L takes XP_PER_LEVEL_STEP * L * (L - 1) / 2 total XP: 0, 100, 300, 600, ...
+pub const XP_PER_LEVEL_STEP: u64 = 100;
+
+/// Adds `amount` XP to a player's league total and emits XpAwarded with their new level.
+pub fn award_xp(player_state: &mut PlayerState, amount: u32) {
+    player_state.xp += amount;
+
+    emit!(XpAwarded {
+        league_id: player_state.league_id,
+        player: player_state.player,
+        amount,
+        xp: player_state.xp,
+        level: player_state.level(),
+    });
+}
+
+/// Total XP needed to reach `level` (level 1 needs none).
+pub fn xp_for_level(level: u32) -> u64 {
+    let level = level.max(1) as u64;
+    XP_PER_LEVEL_STEP * level * (level - 1) / 2
+}
+
+/// Level reached with `xp` total XP, starting at 1.
+pub fn level_for_xp(xp: u64) -> u32 {
+    let mut level = 1;
+    while xp_for_level(level + 1) <= xp {
+        level += 1;
+    }
+    level
+}
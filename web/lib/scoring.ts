@@ -10,6 +10,9 @@
 
 export type Prediction = 'YES' | 'NO';
 
+// Basis points denominator used by the on-chain program (10000 = 1.0)
+const BPS = 10_000;
+
 /**
  * Section 4.1: Calculate draft-time probability for a prediction
  * 
@@ -83,18 +86,79 @@ export function getTierName(pPred: number): 'Favorite' | 'Balanced' | 'Long-shot
  * 
  * @param correct - Whether the prediction was correct
  * @param pPred - Probability of the predicted outcome at draft time (0-1)
- * @returns Final points (rounded)
+ * @returns Final points, identical to the on-chain result
  */
 export function calculateFinalPoints(
     correct: boolean,
     pPred: number
 ): number {
+    return calculateFinalPointsBps(correct, Math.round(pPred * BPS));
+}
+
+/**
+ * Per-league scoring rules as stored on the on-chain `League` account.
+ * Probabilities and multipliers are basis points (10000 = 1.0).
+ */
+export interface ScoringRules {
+    favoriteThresholdBps: number;
+    balancedThresholdBps: number;
+    favoriteMultiplierBps: number;
+    balancedMultiplierBps: number;
+    longshotMultiplierBps: number;
+    lossFactor: number;
+    longshotBonusThresholdBps: number;
+    longshotBonus: number;
+    streakInterval: number;
+    streakBonus: number;
+    cleanSweepBonus: number;
+}
+
+/**
+ * The standard PRD profile, identical to `ScoringRules::default()` on-chain
+ */
+export const STANDARD_SCORING_RULES: ScoringRules = {
+    favoriteThresholdBps: 7_000,
+    balancedThresholdBps: 4_000,
+    favoriteMultiplierBps: 10_000,
+    balancedMultiplierBps: 12_000,
+    longshotMultiplierBps: 15_000,
+    lossFactor: 30,
+    longshotBonusThresholdBps: 2_000,
+    longshotBonus: 10,
+    streakInterval: 5,
+    streakBonus: 25,
+    cleanSweepBonus: 50,
+};
+
+/**
+ * Multiplier in basis points (10000 = 1.0x), mirroring `calculate_multiplier` on-chain
+ */
+function getMultiplierBps(pPredBps: number, rules: ScoringRules): number {
+    if (pPredBps >= rules.favoriteThresholdBps) return rules.favoriteMultiplierBps;
+    if (pPredBps >= rules.balancedThresholdBps) return rules.balancedMultiplierBps;
+    return rules.longshotMultiplierBps;
+}
+
+/**
+ * Fixed-point version of Section 4.3, bit-for-bit identical to the program's
+ * `calculate_win_points` / `calculate_loss_points` (integer math, truncating toward zero).
+ * 
+ * @param correct - Whether the prediction was correct
+ * @param pPredBps - Probability of the predicted outcome in basis points (0-10000)
+ * @param rules - The league's scoring rules (defaults to the standard profile)
+ * @returns Final points
+ */
+export function calculateFinalPointsBps(
+    correct: boolean,
+    pPredBps: number,
+    rules: ScoringRules = STANDARD_SCORING_RULES
+): number {
+    const p = Math.min(Math.max(Math.trunc(pPredBps), 0), BPS);
     if (correct) {
-        const base = 100 * (1 - pPred);
-        const multiplier = getMultiplier(pPred);
-        return Math.round(base * multiplier);
+        // Max numerator is 100 * 10000 * 50000, well within Number.MAX_SAFE_INTEGER
+        return Math.trunc((100 * (BPS - p) * getMultiplierBps(p, rules)) / (BPS * BPS));
     } else {
-        return Math.round(-30 * pPred);
+        return -Math.trunc((rules.lossFactor * p) / BPS);
     }
 }
 